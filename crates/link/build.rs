@@ -0,0 +1,49 @@
+use std::env;
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+fn git_commit() -> String {
+    if let Ok(commit) = env::var("ATORA_GIT_COMMIT") {
+        return commit;
+    }
+
+    Command::new("git")
+        .args(["rev-parse", "--short=12", "HEAD"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| String::from_utf8(output.stdout).ok())
+        .map(|commit| commit.trim().to_string())
+        .filter(|commit| !commit.is_empty())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+// Shared with the build_info tests, which check it against known dates.
+include!("build/rfc3339.rs");
+
+fn build_timestamp() -> String {
+    // SOURCE_DATE_EPOCH keeps reproducible builds reproducible.
+    let secs = match env::var("SOURCE_DATE_EPOCH") {
+        Ok(epoch) => epoch.trim().parse().ok(),
+        Err(_) => SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .ok()
+            .map(|d| d.as_secs()),
+    };
+
+    secs.map_or_else(|| "unknown".to_string(), rfc3339)
+}
+
+fn main() {
+    println!("cargo:rerun-if-env-changed=ATORA_GIT_COMMIT");
+    println!("cargo:rerun-if-env-changed=SOURCE_DATE_EPOCH");
+    println!("cargo:rerun-if-changed=../../.git/HEAD");
+    println!("cargo:rerun-if-changed=../../.git/refs/heads");
+    println!("cargo:rerun-if-changed=src");
+
+    println!("cargo:rustc-env=ATORA_GIT_COMMIT={}", git_commit());
+    println!(
+        "cargo:rustc-env=ATORA_BUILD_TIMESTAMP={}",
+        build_timestamp()
+    );
+}
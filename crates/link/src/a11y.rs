@@ -0,0 +1,444 @@
+use std::collections::HashMap;
+
+use js_sys::{Array, Object, Reflect};
+use wasm_bindgen::prelude::*;
+
+use crate::core::analyze;
+
+const GENERIC_LINK_PHRASES: &[&str] = &[
+    "click",
+    "click here",
+    "continue",
+    "details",
+    "go",
+    "here",
+    "learn more",
+    "link",
+    "more",
+    "more info",
+    "read more",
+    "this",
+    "this link",
+];
+
+const REDUNDANT_ALT_PREFIXES: &[&str] = &[
+    "graphic of",
+    "icon of",
+    "image of",
+    "photo of",
+    "photograph of",
+    "picture of",
+    "screenshot of",
+];
+
+const IMAGE_EXTENSIONS: &[&str] = &[
+    "avif", "bmp", "gif", "heic", "jpeg", "jpg", "png", "svg", "tif", "tiff", "webp",
+];
+
+const MAX_LINK_TEXT_CHARS: usize = 100;
+
+struct Issue {
+    code: &'static str,
+    suggestion: &'static str,
+}
+
+impl Issue {
+    fn to_js(&self) -> JsValue {
+        let obj = Object::new();
+        let _ = Reflect::set(&obj, &"code".into(), &self.code.into());
+        let _ = Reflect::set(&obj, &"suggestion".into(), &self.suggestion.into());
+        obj.into()
+    }
+}
+
+fn issues_to_js(issues: &[Issue]) -> JsValue {
+    issues.iter().map(Issue::to_js).collect::<Array>().into()
+}
+
+/// Lowercases, collapses whitespace and drops surrounding punctuation, so
+/// "  Read   More →" and "read more" compare equal.
+fn normalize(text: &str) -> String {
+    text.split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .trim_matches(|c: char| !c.is_alphanumeric())
+        .to_lowercase()
+}
+
+fn phrase_list(extra: Option<Array>) -> Vec<String> {
+    extra
+        .map(|array| {
+            array
+                .iter()
+                .filter_map(|v| v.as_string())
+                .map(|s| normalize(&s))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn looks_like_url(text: &str) -> bool {
+    let lower = text.trim().to_ascii_lowercase();
+    !lower.contains(char::is_whitespace)
+        && (lower.starts_with("http://")
+            || lower.starts_with("https://")
+            || lower.starts_with("www."))
+}
+
+fn is_shouting(text: &str) -> bool {
+    // Short all-caps strings are usually acronyms ("FAQ", "PDF").
+    let letters: Vec<char> = text.chars().filter(|c| c.is_alphabetic()).collect();
+    letters.len() >= 5 && letters.iter().all(|c| !c.is_lowercase())
+}
+
+fn looks_like_filename(alt: &str, src: &str) -> bool {
+    let alt = alt.trim().to_lowercase();
+    if alt.contains(char::is_whitespace) {
+        return false;
+    }
+
+    let has_image_extension = alt
+        .rsplit_once('.')
+        .is_some_and(|(stem, ext)| !stem.is_empty() && IMAGE_EXTENSIONS.contains(&ext));
+    let is_camera_name = ["img_", "img-", "dsc_", "dsc", "dcim", "pxl_", "screenshot_"]
+        .iter()
+        .any(|prefix| {
+            alt.strip_prefix(prefix)
+                .is_some_and(|rest| rest.starts_with(|c: char| c.is_ascii_digit()))
+        });
+
+    let file = src
+        .split(['?', '#'])
+        .next()
+        .unwrap_or_default()
+        .rsplit('/')
+        .next()
+        .unwrap_or_default();
+    let stem = file
+        .rsplit_once('.')
+        .map_or(file, |(stem, _)| stem)
+        .to_lowercase();
+    let matches_src = !file.is_empty() && (alt == file.to_lowercase() || alt == stem);
+
+    has_image_extension || is_camera_name || matches_src
+}
+
+fn link_text_issues(text: &str, extra: &[String]) -> Vec<Issue> {
+    let normalized = normalize(text);
+    if normalized.is_empty() {
+        return vec![Issue {
+            code: "empty",
+            suggestion: "Give the link visible text or an aria-label describing its destination.",
+        }];
+    }
+
+    let mut issues = Vec::new();
+    let is_generic =
+        GENERIC_LINK_PHRASES.contains(&normalized.as_str()) || extra.contains(&normalized);
+    if is_generic {
+        issues.push(Issue {
+            code: "generic",
+            suggestion: "Describe where the link goes, e.g. \"Read the pricing guide\" instead of \"Read more\".",
+        });
+    }
+    if looks_like_url(text) {
+        issues.push(Issue {
+            code: "raw_url",
+            suggestion: "Replace the URL with a short description of the destination.",
+        });
+    }
+    if is_shouting(text) {
+        issues.push(Issue {
+            code: "all_caps",
+            suggestion: "Use sentence case; screen readers may spell out all-caps text letter by letter.",
+        });
+    }
+    if text.trim().chars().count() > MAX_LINK_TEXT_CHARS {
+        issues.push(Issue {
+            code: "too_long",
+            suggestion: "Shorten the link text to a concise description of the destination.",
+        });
+    }
+
+    issues
+}
+
+fn alt_text_issues(
+    alt: Option<&str>,
+    src: &str,
+    link_text: Option<&str>,
+    extra: &[String],
+) -> Vec<Issue> {
+    let Some(alt) = alt else {
+        return vec![Issue {
+            code: "missing",
+            suggestion: "Add an alt attribute; use alt=\"\" if the image is purely decorative.",
+        }];
+    };
+
+    let normalized = normalize(alt);
+    if normalized.is_empty() {
+        return vec![Issue {
+            code: "empty",
+            suggestion: "Empty alt marks the image as decorative; describe it if it conveys information.",
+        }];
+    }
+
+    let mut issues = Vec::new();
+    if looks_like_filename(alt, src) {
+        issues.push(Issue {
+            code: "filename",
+            suggestion: "Replace the file name with a description of what the image shows.",
+        });
+    }
+
+    let without_article = ["a ", "an ", "the "]
+        .iter()
+        .find_map(|article| normalized.strip_prefix(article))
+        .unwrap_or(&normalized);
+    let is_redundant = REDUNDANT_ALT_PREFIXES
+        .iter()
+        .map(|prefix| prefix.to_string())
+        .chain(extra.iter().cloned())
+        .any(|prefix| {
+            without_article.starts_with(&format!("{prefix} ")) || without_article == prefix
+        });
+    if is_redundant {
+        issues.push(Issue {
+            code: "redundant_prefix",
+            suggestion: "Drop \"image of\"/\"picture of\"; screen readers already announce images.",
+        });
+    }
+
+    if link_text.is_some_and(|text| normalize(text) == normalized) {
+        issues.push(Issue {
+            code: "same_as_link_text",
+            suggestion: "Use alt=\"\" when the adjacent link text already describes the image.",
+        });
+    }
+
+    issues
+}
+
+/// Returns `{code, suggestion}` objects for accessibility problems with a
+/// link's visible text. `extra_phrases` extends the built-in generic phrases.
+#[wasm_bindgen]
+pub fn check_link_text(text: &str, extra_phrases: Option<Array>) -> JsValue {
+    issues_to_js(&link_text_issues(text, &phrase_list(extra_phrases)))
+}
+
+/// Returns `{code, suggestion}` objects for problems with an image's alt text.
+/// Pass `undefined` for `alt` when the attribute is missing and `""` when it is
+/// present but empty; these are reported as `missing` and `empty`.
+#[wasm_bindgen]
+pub fn check_alt_text(
+    alt: Option<String>,
+    src: &str,
+    adjacent_link_text: Option<String>,
+    extra_prefixes: Option<Array>,
+) -> JsValue {
+    let extra = phrase_list(extra_prefixes);
+    issues_to_js(&alt_text_issues(
+        alt.as_deref(),
+        src,
+        adjacent_link_text.as_deref(),
+        &extra,
+    ))
+}
+
+/// Finds link texts that point to more than one destination: every pair of
+/// distinct normalized hrefs sharing a normalized text, as `(text, a, b)` in
+/// first-seen order. `text` is the first spelling seen.
+fn duplicate_pairs(entries: &[(String, String)]) -> Vec<(String, String, String)> {
+    let mut groups: Vec<(String, Vec<String>)> = Vec::new();
+    let mut by_text: HashMap<String, usize> = HashMap::new();
+
+    for (text, href) in entries {
+        let key = normalize(text);
+        if key.is_empty() {
+            continue;
+        }
+
+        let destination =
+            analyze(href, None).map_or_else(|_| href.trim().to_string(), |info| info.normalized());
+        match by_text.get(&key) {
+            Some(&index) => {
+                let hrefs = &mut groups[index].1;
+                if !hrefs.contains(&destination) {
+                    hrefs.push(destination);
+                }
+            }
+            None => {
+                by_text.insert(key, groups.len());
+                groups.push((text.trim().to_string(), vec![destination]));
+            }
+        }
+    }
+
+    let mut pairs = Vec::new();
+    for (text, hrefs) in &groups {
+        for (i, a) in hrefs.iter().enumerate() {
+            for b in &hrefs[i + 1..] {
+                pairs.push((text.clone(), a.clone(), b.clone()));
+            }
+        }
+    }
+    pairs
+}
+
+/// Reports same-text-different-href pairs from `{text, href}` entries as
+/// `{text, hrefs: [a, b]}`, one object per pair. Texts are compared
+/// case- and whitespace-insensitively and hrefs after normalization, so
+/// `"Docs"` → `/a` and `" docs "` → `/b` form one pair; three destinations for
+/// one text give three pairs.
+#[wasm_bindgen]
+pub fn duplicate_link_texts(entries: Array) -> Array {
+    let entries: Vec<(String, String)> = entries
+        .iter()
+        .filter_map(|entry| {
+            let field = |name: &str| {
+                Reflect::get(&entry, &name.into())
+                    .ok()
+                    .and_then(|v| v.as_string())
+            };
+            Some((field("text")?, field("href")?))
+        })
+        .collect();
+
+    duplicate_pairs(&entries)
+        .into_iter()
+        .map(|(text, a, b)| {
+            let obj = Object::new();
+            let _ = Reflect::set(&obj, &"text".into(), &text.into());
+            let _ = Reflect::set(&obj, &"hrefs".into(), &Array::of2(&a.into(), &b.into()));
+            JsValue::from(obj)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn link_codes(text: &str, extra: &[&str]) -> Vec<&'static str> {
+        let extra: Vec<String> = extra.iter().map(|s| normalize(s)).collect();
+        link_text_issues(text, &extra)
+            .iter()
+            .map(|issue| issue.code)
+            .collect()
+    }
+
+    fn alt_codes(alt: Option<&str>, src: &str, link_text: Option<&str>) -> Vec<&'static str> {
+        alt_text_issues(alt, src, link_text, &[])
+            .iter()
+            .map(|issue| issue.code)
+            .collect()
+    }
+
+    #[test]
+    fn flags_generic_link_text_ignoring_case_and_whitespace() {
+        for text in ["click here", "  Read   MORE  ", "Link", "learn more →"] {
+            assert_eq!(link_codes(text, &[]), ["generic"], "{text}");
+        }
+        assert!(link_codes("Read the pricing guide", &[]).is_empty());
+    }
+
+    #[test]
+    fn extra_phrases_extend_the_generic_list() {
+        assert!(link_codes("Weiterlesen", &[]).is_empty());
+        assert_eq!(link_codes(" weiterLESEN ", &["Weiterlesen"]), ["generic"]);
+        assert_eq!(link_codes("see   all", &["  See All "]), ["generic"]);
+    }
+
+    #[test]
+    fn flags_empty_raw_url_shouting_and_long_text() {
+        assert_eq!(link_codes("", &[]), ["empty"]);
+        assert_eq!(link_codes(" \n\t ", &[]), ["empty"]);
+        assert_eq!(link_codes("https://example.com/pricing", &[]), ["raw_url"]);
+        assert_eq!(link_codes("www.example.com", &[]), ["raw_url"]);
+        assert_eq!(link_codes("DOWNLOAD THE REPORT", &[]), ["all_caps"]);
+        assert!(link_codes("FAQ", &[]).is_empty());
+        assert_eq!(link_codes(&"word ".repeat(30), &[]), ["too_long"]);
+        assert!(link_codes(&"a".repeat(MAX_LINK_TEXT_CHARS), &[]).is_empty());
+    }
+
+    #[test]
+    fn distinguishes_missing_from_empty_alt() {
+        assert_eq!(alt_codes(None, "/a.png", None), ["missing"]);
+        assert_eq!(alt_codes(Some(""), "/a.png", None), ["empty"]);
+        assert_eq!(alt_codes(Some("   "), "/a.png", None), ["empty"]);
+    }
+
+    #[test]
+    fn flags_filenames_as_alt() {
+        for alt in ["IMG_1234.jpg", "dsc_0042", "hero.webp", "team-photo"] {
+            assert_eq!(
+                alt_codes(Some(alt), "/img/team-photo.png?w=300", None),
+                ["filename"],
+                "{alt}"
+            );
+        }
+        assert!(alt_codes(Some("Our team at the 2024 offsite"), "/img/team.png", None).is_empty());
+    }
+
+    #[test]
+    fn flags_redundant_prefixes() {
+        for alt in [
+            "Image of a red bicycle",
+            "  a PICTURE of the team",
+            "Photo of",
+        ] {
+            assert_eq!(
+                alt_codes(Some(alt), "/a.png", None),
+                ["redundant_prefix"],
+                "{alt}"
+            );
+        }
+        assert!(alt_codes(Some("Imagery from the archive"), "/a.png", None).is_empty());
+
+        let extra = vec![normalize("Bild von")];
+        let codes: Vec<_> = alt_text_issues(Some("Bild von einem Hund"), "/a.png", None, &extra)
+            .iter()
+            .map(|issue| issue.code)
+            .collect();
+        assert_eq!(codes, ["redundant_prefix"]);
+    }
+
+    #[test]
+    fn flags_alt_matching_adjacent_link_text() {
+        assert_eq!(
+            alt_codes(Some("Pricing  guide"), "/a.png", Some(" pricing guide ")),
+            ["same_as_link_text"]
+        );
+        assert!(alt_codes(Some("Pricing guide"), "/a.png", Some("Read the FAQ")).is_empty());
+    }
+
+    #[test]
+    fn reports_same_text_different_href_pairs() {
+        let entries: Vec<(String, String)> = [
+            ("Docs", "https://example.com/a"),
+            (" docs ", "https://example.com/b"),
+            ("DOCS", "https://EXAMPLE.com/a"),
+            ("Pricing", "https://example.com/pricing"),
+            ("Pricing", "https://example.com/pricing"),
+            ("docs", "https://example.com/c"),
+        ]
+        .iter()
+        .map(|(text, href)| (text.to_string(), href.to_string()))
+        .collect();
+
+        let pairs = duplicate_pairs(&entries);
+        let pairs: Vec<_> = pairs
+            .iter()
+            .map(|(text, a, b)| (text.as_str(), a.as_str(), b.as_str()))
+            .collect();
+        assert_eq!(
+            pairs,
+            [
+                ("Docs", "https://example.com/a", "https://example.com/b"),
+                ("Docs", "https://example.com/a", "https://example.com/c"),
+                ("Docs", "https://example.com/b", "https://example.com/c"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,109 @@
+use js_sys::{Array, Object, Reflect};
+use wasm_bindgen::prelude::*;
+
+use crate::a11y::check_link_text;
+use crate::core::{LinkKind, analyze_link};
+use crate::feeds::discover_feed_urls;
+use crate::hints::plan_link_hints;
+use crate::nav_stack::NavStack;
+use crate::srcset::parse_srcset;
+use crate::url_utils::{display_domain, fits_url_budget};
+
+/// Cargo features compiled into this build. The crate has none yet; add each
+/// new feature here behind a matching `cfg!(feature = "...")` check.
+const ENABLED_FEATURES: &[&str] = &[];
+
+/// Returns `{crate_name, version, git_commit, build_timestamp, features}` so a
+/// running bundle can be traced back to the build that produced it.
+/// `git_commit` and `build_timestamp` are `"unknown"` when the build had no
+/// access to git or a clock.
+#[wasm_bindgen]
+pub fn get_build_info() -> Result<JsValue, JsValue> {
+    let obj = Object::new();
+    Reflect::set(&obj, &"crate_name".into(), &env!("CARGO_PKG_NAME").into())?;
+    Reflect::set(&obj, &"version".into(), &env!("CARGO_PKG_VERSION").into())?;
+    Reflect::set(&obj, &"git_commit".into(), &env!("ATORA_GIT_COMMIT").into())?;
+    Reflect::set(
+        &obj,
+        &"build_timestamp".into(),
+        &env!("ATORA_BUILD_TIMESTAMP").into(),
+    )?;
+    Reflect::set(&obj, &"features".into(), &get_enabled_features())?;
+    Ok(obj.into())
+}
+
+#[wasm_bindgen]
+pub fn get_enabled_features() -> Array {
+    ENABLED_FEATURES
+        .iter()
+        .map(|feature| JsValue::from(*feature))
+        .collect()
+}
+
+/// Exercises one function from each module so the app can verify at startup
+/// that the WASM bundle loaded and linked correctly.
+#[wasm_bindgen]
+pub fn health_check() -> bool {
+    let link_ok = analyze_link(
+        "https://example.com/report.pdf",
+        Some("https://example.org".into()),
+    )
+    .is_ok_and(|info| info.kind() == LinkKind::External && info.is_downloadable());
+    let srcset_ok =
+        parse_srcset("a.png 1x, b.png 2x").is_ok_and(|candidates| candidates.length() == 2);
+    let a11y_ok = Array::from(&check_link_text("click here", None)).length() == 1;
+
+    let nav_ok = NavStack::new(Some(2), None).is_ok_and(|mut stack| {
+        stack.push("https://example.com/a");
+        stack.push("https://example.com/b");
+        stack.back().as_deref() == Some("https://example.com/a")
+    });
+
+    let links = Array::of2(&"/a".into(), &"/b".into());
+    let hints_ok = plan_link_hints(links, "https://example.com/", JsValue::UNDEFINED)
+        .ok()
+        .and_then(|plan| Reflect::get(&plan, &"prefetch".into()).ok())
+        .is_some_and(|prefetch| Array::from(&prefetch).length() == 2);
+
+    let feeds_ok = discover_feed_urls(
+        r#"<link rel="alternate" type="application/rss+xml" href="/feed.xml">"#,
+        "https://example.com/",
+    )
+    .is_ok_and(|feeds| feeds.length() == 1);
+
+    let url_utils_ok = display_domain("https://www.xn--bcher-kva.de/") == "bücher.de"
+        && fits_url_budget("https://example.com/", 20);
+
+    link_ok && srcset_ok && a11y_ok && nav_ok && hints_ok && feeds_ok && url_utils_ok
+}
+
+#[cfg(test)]
+mod tests {
+    include!(concat!(env!("CARGO_MANIFEST_DIR"), "/build/rfc3339.rs"));
+
+    #[test]
+    fn version_matches_manifest() {
+        let manifest = include_str!("../Cargo.toml");
+        let version = manifest
+            .lines()
+            .skip_while(|line| line.trim() != "[package]")
+            .find_map(|line| line.trim().strip_prefix("version = "))
+            .map(|value| value.trim_matches(['"', '\'']));
+        assert_eq!(version, Some(env!("CARGO_PKG_VERSION")));
+    }
+
+    #[test]
+    fn rfc3339_formats_known_dates() {
+        assert_eq!(rfc3339(0), "1970-01-01T00:00:00Z");
+        assert_eq!(rfc3339(951_782_400), "2000-02-29T00:00:00Z");
+        assert_eq!(rfc3339(1_709_251_199), "2024-02-29T23:59:59Z");
+        assert_eq!(rfc3339(1_735_689_600), "2025-01-01T00:00:00Z");
+        assert_eq!(rfc3339(4_102_444_800), "2100-01-01T00:00:00Z");
+    }
+
+    #[test]
+    fn build_timestamp_is_rfc3339_or_unknown() {
+        let timestamp = env!("ATORA_BUILD_TIMESTAMP");
+        assert!(timestamp == "unknown" || (timestamp.len() == 20 && timestamp.ends_with('Z')));
+    }
+}
@@ -0,0 +1,283 @@
+use url::{ParseError, Url};
+use wasm_bindgen::prelude::*;
+
+const DOWNLOADABLE_EXTENSIONS: &[&str] = &[
+    "7z", "apk", "avi", "bz2", "csv", "deb", "dmg", "doc", "docx", "epub", "exe", "flac", "gz",
+    "iso", "mkv", "mov", "mp3", "mp4", "msi", "odp", "ods", "odt", "pdf", "pkg", "ppt", "pptx",
+    "rar", "rpm", "tar", "tgz", "wav", "xls", "xlsx", "xz", "zip",
+];
+
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LinkKind {
+    External = "external",
+    Internal = "internal",
+    Anchor = "anchor",
+    Mailto = "mailto",
+    Tel = "tel",
+    Other = "other",
+}
+
+/// Everything the Link component needs to know about an href, computed in a
+/// single call.
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct LinkInfo {
+    kind: LinkKind,
+    is_secure: bool,
+    protocol: Option<String>,
+    host: Option<String>,
+    path: String,
+    normalized: String,
+    is_downloadable: bool,
+}
+
+#[wasm_bindgen]
+impl LinkInfo {
+    #[wasm_bindgen(getter)]
+    pub fn kind(&self) -> LinkKind {
+        self.kind
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn is_secure(&self) -> bool {
+        self.is_secure
+    }
+
+    /// The scheme including the trailing colon (`"https:"`), like `URL.protocol`.
+    #[wasm_bindgen(getter)]
+    pub fn protocol(&self) -> Option<String> {
+        self.protocol.clone()
+    }
+
+    /// Host and non-default port, like `URL.host`.
+    #[wasm_bindgen(getter)]
+    pub fn host(&self) -> Option<String> {
+        self.host.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn path(&self) -> String {
+        self.path.clone()
+    }
+
+    /// The resolved, serialized URL, or the trimmed href when a relative link
+    /// could not be resolved because no base origin was given.
+    #[wasm_bindgen(getter)]
+    pub fn normalized(&self) -> String {
+        self.normalized.clone()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn is_downloadable(&self) -> bool {
+        self.is_downloadable
+    }
+}
+
+impl LinkInfo {
+    fn from_url(url: &Url, kind: LinkKind) -> Self {
+        let host = url.host_str().map(|host| match url.port() {
+            Some(port) => format!("{host}:{port}"),
+            None => host.to_string(),
+        });
+
+        Self {
+            kind,
+            is_secure: matches!(url.scheme(), "https" | "wss"),
+            protocol: Some(format!("{}:", url.scheme())),
+            host,
+            path: url.path().to_string(),
+            normalized: url.to_string(),
+            is_downloadable: is_downloadable_path(url.path()),
+        }
+    }
+
+    fn unresolved(href: &str, kind: LinkKind) -> Self {
+        let path = href.split(['?', '#']).next().unwrap_or_default();
+
+        Self {
+            kind,
+            is_secure: false,
+            protocol: None,
+            host: None,
+            path: path.to_string(),
+            normalized: href.to_string(),
+            is_downloadable: is_downloadable_path(path),
+        }
+    }
+}
+
+fn is_downloadable_path(path: &str) -> bool {
+    let file = path.rsplit('/').next().unwrap_or_default();
+    match file.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() => {
+            DOWNLOADABLE_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str())
+        }
+        _ => false,
+    }
+}
+
+fn classify(url: &Url, base: Option<&Url>) -> LinkKind {
+    match url.scheme() {
+        "mailto" => LinkKind::Mailto,
+        "tel" => LinkKind::Tel,
+        "http" | "https" => match base {
+            Some(base) if url.origin() == base.origin() => LinkKind::Internal,
+            _ => LinkKind::External,
+        },
+        _ => LinkKind::Other,
+    }
+}
+
+pub(crate) fn analyze(href: &str, base_origin: Option<&str>) -> Result<LinkInfo, String> {
+    let href = href.trim();
+    let base = base_origin
+        .map(Url::parse)
+        .transpose()
+        .map_err(|e| format!("invalid base origin: {e}"))?;
+
+    if href.starts_with('#') {
+        return Ok(match &base {
+            Some(base) => LinkInfo::from_url(
+                &base.join(href).unwrap_or_else(|_| base.clone()),
+                LinkKind::Anchor,
+            ),
+            None => LinkInfo::unresolved(href, LinkKind::Anchor),
+        });
+    }
+
+    match Url::parse(href) {
+        Ok(url) => Ok(LinkInfo::from_url(&url, classify(&url, base.as_ref()))),
+        Err(ParseError::RelativeUrlWithoutBase) => match &base {
+            Some(base) => {
+                let url = base.join(href).map_err(|e| format!("invalid href: {e}"))?;
+                Ok(LinkInfo::from_url(&url, classify(&url, Some(base))))
+            }
+            // A protocol-relative href always names a host, so it can only be
+            // internal when a base origin says so.
+            None if href.starts_with("//") => {
+                let url = Url::parse(&format!("https:{href}"))
+                    .map_err(|e| format!("invalid href: {e}"))?;
+                Ok(LinkInfo {
+                    is_secure: false,
+                    protocol: None,
+                    normalized: href.to_string(),
+                    ..LinkInfo::from_url(&url, LinkKind::External)
+                })
+            }
+            None => Ok(LinkInfo::unresolved(href, LinkKind::Internal)),
+        },
+        Err(e) => Err(format!("invalid href: {e}")),
+    }
+}
+
+/// Classifies and normalizes an href. This is the entry point for the Link
+/// component; `is_external` is a separate, plain prefix check kept for
+/// existing callers and does not go through it.
+///
+/// When `base_origin` is given, relative links are resolved against it and
+/// absolute links on the same origin count as internal. An empty href refers
+/// to the current document, so it is internal: it resolves to the base origin,
+/// or is left empty without one.
+#[wasm_bindgen]
+pub fn analyze_link(href: &str, base_origin: Option<String>) -> Result<LinkInfo, JsValue> {
+    analyze(href, base_origin.as_deref()).map_err(|e| JsValue::from_str(&e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: Option<&str> = Some("https://example.com");
+
+    fn kind(href: &str, base: Option<&str>) -> LinkKind {
+        analyze(href, base).unwrap().kind
+    }
+
+    #[test]
+    fn classifies_each_kind() {
+        assert_eq!(kind("https://other.org/x", BASE), LinkKind::External);
+        assert_eq!(kind("https://other.org/x", None), LinkKind::External);
+        assert_eq!(kind("HTTPS://EXAMPLE.COM:443/x", BASE), LinkKind::Internal);
+        assert_eq!(kind("http://example.com/x", BASE), LinkKind::External);
+        assert_eq!(kind("/about", BASE), LinkKind::Internal);
+        assert_eq!(kind("about", None), LinkKind::Internal);
+        assert_eq!(kind("#top", BASE), LinkKind::Anchor);
+        assert_eq!(kind("#top", None), LinkKind::Anchor);
+        assert_eq!(kind("mailto:a@b.com", BASE), LinkKind::Mailto);
+        assert_eq!(kind("tel:+15551234", None), LinkKind::Tel);
+        assert_eq!(kind("ftp://example.com/f", BASE), LinkKind::Other);
+        assert_eq!(kind("javascript:void(0)", None), LinkKind::Other);
+    }
+
+    #[test]
+    fn resolves_relative_links_against_base_origin() {
+        let info = analyze(" /docs/../guide?x=1#y ", BASE).unwrap();
+        assert_eq!(info.host.as_deref(), Some("example.com"));
+        assert_eq!(info.protocol.as_deref(), Some("https:"));
+        assert_eq!(info.path, "/guide");
+        assert_eq!(info.normalized, "https://example.com/guide?x=1#y");
+        assert!(info.is_secure);
+
+        let info = analyze("#top", Some("http://localhost:3000/app")).unwrap();
+        assert_eq!(info.host.as_deref(), Some("localhost:3000"));
+        assert_eq!(info.normalized, "http://localhost:3000/app#top");
+        assert!(!info.is_secure);
+
+        let info = analyze("/docs?x=1", None).unwrap();
+        assert_eq!(info.host, None);
+        assert_eq!(info.protocol, None);
+        assert_eq!(info.path, "/docs");
+        assert_eq!(info.normalized, "/docs?x=1");
+    }
+
+    #[test]
+    fn protocol_relative_hrefs() {
+        let info = analyze("//cdn.example.net/app.js", None).unwrap();
+        assert_eq!(info.kind, LinkKind::External);
+        assert_eq!(info.host.as_deref(), Some("cdn.example.net"));
+        assert_eq!(info.protocol, None);
+        assert!(!info.is_secure);
+        assert_eq!(info.normalized, "//cdn.example.net/app.js");
+
+        let info = analyze("//cdn.example.net/app.js", Some("http://example.com")).unwrap();
+        assert_eq!(info.kind, LinkKind::External);
+        assert_eq!(info.protocol.as_deref(), Some("http:"));
+        assert_eq!(info.normalized, "http://cdn.example.net/app.js");
+
+        assert_eq!(kind("//example.com/x", BASE), LinkKind::Internal);
+    }
+
+    #[test]
+    fn detects_downloadable_extensions() {
+        let downloadable = |href| analyze(href, BASE).unwrap().is_downloadable;
+        assert!(downloadable("/files/report.PDF"));
+        assert!(downloadable("https://other.org/a/archive.tar.gz?v=2"));
+        assert!(downloadable("setup.exe#install"));
+        assert!(!downloadable("/files/report.pdf/"));
+        assert!(!downloadable("/.zip"));
+        assert!(!downloadable("/page.html"));
+        assert!(!downloadable("/pdf"));
+        assert!(analyze("files/a.csv?x", None).unwrap().is_downloadable);
+    }
+
+    #[test]
+    fn rejects_invalid_input() {
+        assert!(analyze("/x", Some("not an origin")).is_err());
+        assert!(analyze("/x", Some("/relative")).is_err());
+        assert!(analyze("https://exa mple.com/", None).is_err());
+        assert!(analyze("http://[::1/", BASE).is_err());
+    }
+
+    #[test]
+    fn empty_href_is_the_current_document() {
+        let info = analyze("  ", None).unwrap();
+        assert_eq!(info.kind, LinkKind::Internal);
+        assert_eq!(info.normalized, "");
+        assert_eq!(info.path, "");
+
+        let info = analyze("", Some("https://example.com/page")).unwrap();
+        assert_eq!(info.kind, LinkKind::Internal);
+        assert_eq!(info.normalized, "https://example.com/page");
+    }
+}
@@ -0,0 +1,734 @@
+use std::collections::HashSet;
+
+use js_sys::{Array, Object, Reflect};
+use url::Url;
+use wasm_bindgen::prelude::*;
+
+use crate::url_utils::canonical_key;
+
+const MAX_OUTLINE_DEPTH: usize = 64;
+
+struct Tag {
+    name: String,
+    closing: bool,
+    self_closing: bool,
+    attrs: Vec<(String, String)>,
+}
+
+impl Tag {
+    fn attr(&self, name: &str) -> Option<&str> {
+        self.attrs
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+fn decode_entities(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut rest = value;
+
+    while let Some(start) = rest.find('&') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        let decoded = rest.find(';').filter(|&end| end <= 10).and_then(|end| {
+            let entity = &rest[1..end];
+            let c = match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                _ => match entity.strip_prefix('#') {
+                    Some(hex) if hex.starts_with(['x', 'X']) => {
+                        u32::from_str_radix(&hex[1..], 16).ok()
+                    }
+                    Some(dec) => dec.parse().ok(),
+                    None => None,
+                }
+                .and_then(char::from_u32),
+            };
+            c.map(|c| (c, end))
+        });
+
+        match decoded {
+            Some((c, end)) => {
+                out.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+
+    out.push_str(rest);
+    out
+}
+
+fn escape_xml(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Returns the index of the `>` closing the tag that starts at `start`,
+/// ignoring any `>` inside quoted attribute values.
+fn find_tag_end(source: &str, start: usize) -> Option<usize> {
+    let mut quote = None;
+    for (i, b) in source.bytes().enumerate().skip(start) {
+        match (quote, b) {
+            (Some(q), b) if b == q => quote = None,
+            (Some(_), _) => {}
+            (None, b'"' | b'\'') => quote = Some(b),
+            (None, b'>') => return Some(i),
+            _ => {}
+        }
+    }
+    None
+}
+
+fn parse_attributes(body: &str) -> Vec<(String, String)> {
+    let mut attrs = Vec::new();
+    let mut chars = body.char_indices().peekable();
+
+    while let Some(&(start, c)) = chars.peek() {
+        if c.is_whitespace() || c == '/' {
+            chars.next();
+            continue;
+        }
+
+        let mut end = start;
+        while let Some(&(i, c)) = chars.peek() {
+            if c.is_whitespace() || c == '=' || c == '/' {
+                break;
+            }
+            end = i + c.len_utf8();
+            chars.next();
+        }
+        let name = body[start..end].to_ascii_lowercase();
+
+        while chars.peek().is_some_and(|&(_, c)| c.is_whitespace()) {
+            chars.next();
+        }
+        if chars.peek().is_none_or(|&(_, c)| c != '=') {
+            attrs.push((name, String::new()));
+            continue;
+        }
+        chars.next();
+        while chars.peek().is_some_and(|&(_, c)| c.is_whitespace()) {
+            chars.next();
+        }
+
+        let value = match chars.peek() {
+            Some(&(i, q)) if q == '"' || q == '\'' => {
+                chars.next();
+                let close = body[i + 1..].find(q).map_or(body.len(), |p| i + 1 + p);
+                while chars.peek().is_some_and(|&(j, _)| j <= close) {
+                    chars.next();
+                }
+                &body[i + 1..close]
+            }
+            Some(&(i, _)) => {
+                let mut end = body.len();
+                while let Some(&(j, c)) = chars.peek() {
+                    if c.is_whitespace() {
+                        end = j;
+                        break;
+                    }
+                    chars.next();
+                }
+                // In `<link href=/feed.rss/>` the slash closes the tag, it is
+                // not part of the value.
+                let value = &body[i..end];
+                match value.strip_suffix('/') {
+                    Some(trimmed) if end == body.len() && !trimmed.is_empty() => trimmed,
+                    _ => value,
+                }
+            }
+            None => "",
+        };
+        attrs.push((name, decode_entities(value)));
+    }
+
+    attrs
+}
+
+/// Byte offset of the first ASCII-case-insensitive match of `needle` in
+/// `haystack` at or after `from`. `needle` must start with an ASCII byte so
+/// the offset is a char boundary.
+fn find_ignore_case(haystack: &str, from: usize, needle: &str) -> Option<usize> {
+    haystack.as_bytes()[from..]
+        .windows(needle.len())
+        .position(|window| window.eq_ignore_ascii_case(needle.as_bytes()))
+        .map(|offset| from + offset)
+}
+
+/// Tokenizes start and end tags, skipping comments, doctypes, processing
+/// instructions and the contents of `<script>` and `<style>`. Tag and attribute
+/// names are lowercased. A `<` that cannot start a tag is treated as text.
+fn tags(source: &str) -> impl Iterator<Item = Tag> + '_ {
+    let mut pos = 0;
+
+    std::iter::from_fn(move || {
+        loop {
+            let start = pos + source[pos..].find('<')?;
+            pos = start + 1;
+
+            let starts_tag = source[pos..]
+                .chars()
+                .next()
+                .is_some_and(|c| c.is_ascii_alphabetic() || matches!(c, '/' | '!' | '?'));
+            if !starts_tag {
+                continue;
+            }
+
+            if source[start..].starts_with("<!--") {
+                pos = source[start..]
+                    .find("-->")
+                    .map_or(source.len(), |end| start + end + 3);
+                continue;
+            }
+
+            // An unterminated tag is text; keep scanning after its `<`.
+            let Some(end) = find_tag_end(source, start) else {
+                continue;
+            };
+            pos = end + 1;
+
+            let inner = &source[start + 1..end];
+            let (closing, inner) = match inner.strip_prefix('/') {
+                Some(rest) => (true, rest),
+                None => (false, inner),
+            };
+            let name_len = inner
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-' || c == ':'))
+                .unwrap_or(inner.len());
+            if name_len == 0 {
+                continue;
+            }
+
+            let name = inner[..name_len].to_ascii_lowercase();
+            if !closing && matches!(name.as_str(), "script" | "style") {
+                let close = format!("</{name}");
+                pos = find_ignore_case(source, pos, &close).unwrap_or(source.len());
+            }
+
+            let self_closing = inner.trim_end().ends_with('/');
+            return Some(Tag {
+                name,
+                closing,
+                self_closing,
+                attrs: if closing {
+                    Vec::new()
+                } else {
+                    parse_attributes(&inner[name_len..])
+                },
+            });
+        }
+    })
+}
+
+fn feed_type(tag: &Tag, url: &Url) -> Option<&'static str> {
+    // Drop MIME parameters such as "; charset=utf-8".
+    let essence = tag
+        .attr("type")
+        .map(|t| t.split(';').next().unwrap_or(t).trim().to_ascii_lowercase());
+    match essence {
+        Some(t) if !t.is_empty() => match t.as_str() {
+            "application/rss+xml" | "application/rdf+xml" => Some("application/rss+xml"),
+            "application/atom+xml" => Some("application/atom+xml"),
+            "application/feed+json" | "application/json+feed" => Some("application/feed+json"),
+            _ => None,
+        },
+        // Sloppy markup often omits the type; fall back to the file extension.
+        _ => {
+            let path = url.path().to_ascii_lowercase();
+            if path.ends_with(".atom") {
+                Some("application/atom+xml")
+            } else if path.ends_with(".rss") || path.ends_with(".xml") || path.ends_with(".rdf") {
+                Some("application/rss+xml")
+            } else {
+                None
+            }
+        }
+    }
+}
+
+struct Feed {
+    url: String,
+    kind: &'static str,
+    title: Option<String>,
+}
+
+fn discover(html: &str, base_url: &str) -> Result<Vec<Feed>, String> {
+    let base = Url::parse(base_url).map_err(|e| format!("invalid base URL: {e}"))?;
+    let mut seen = HashSet::new();
+    let mut feeds = Vec::new();
+
+    for tag in tags(html).filter(|t| t.name == "link" && !t.closing) {
+        let is_alternate = tag.attr("rel").is_some_and(|rel| {
+            rel.split_ascii_whitespace()
+                .any(|r| r.eq_ignore_ascii_case("alternate"))
+        });
+        let Some(href) = tag.attr("href").map(str::trim).filter(|h| !h.is_empty()) else {
+            continue;
+        };
+        if !is_alternate {
+            continue;
+        }
+
+        let Ok(url) = base.join(href) else { continue };
+        let Some(kind) = feed_type(&tag, &url) else {
+            continue;
+        };
+        if !seen.insert(canonical_key(url.as_str(), true)) {
+            continue;
+        }
+
+        let title = tag
+            .attr("title")
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+            .map(String::from);
+        feeds.push(Feed {
+            url: url.to_string(),
+            kind,
+            title,
+        });
+    }
+
+    Ok(feeds)
+}
+
+#[derive(Debug, PartialEq)]
+struct Outline {
+    text: String,
+    title: Option<String>,
+    kind: Option<String>,
+    xml_url: Option<String>,
+    html_url: Option<String>,
+    children: Vec<Outline>,
+}
+
+impl Outline {
+    fn from_tag(tag: &Tag) -> Self {
+        let attr = |name: &str| tag.attr(name).filter(|v| !v.is_empty()).map(String::from);
+        let title = attr("title");
+
+        Self {
+            text: attr("text").or_else(|| title.clone()).unwrap_or_default(),
+            title,
+            kind: attr("type"),
+            xml_url: attr("xmlurl"),
+            html_url: attr("htmlurl"),
+            children: Vec::new(),
+        }
+    }
+
+    fn to_js(&self) -> JsValue {
+        let opt = |v: &Option<String>| v.as_deref().map_or(JsValue::NULL, JsValue::from);
+        let obj = Object::new();
+        let _ = Reflect::set(&obj, &"text".into(), &self.text.as_str().into());
+        let _ = Reflect::set(&obj, &"title".into(), &opt(&self.title));
+        let _ = Reflect::set(&obj, &"type".into(), &opt(&self.kind));
+        let _ = Reflect::set(&obj, &"xml_url".into(), &opt(&self.xml_url));
+        let _ = Reflect::set(&obj, &"html_url".into(), &opt(&self.html_url));
+        let children: Array = self.children.iter().map(Outline::to_js).collect();
+        let _ = Reflect::set(&obj, &"children".into(), &children);
+        obj.into()
+    }
+
+    fn from_js(value: &JsValue, depth: usize) -> Result<Self, String> {
+        if depth >= MAX_OUTLINE_DEPTH {
+            return Err(format!(
+                "outlines are nested deeper than {MAX_OUTLINE_DEPTH} levels"
+            ));
+        }
+
+        let field = |name: &str| {
+            Reflect::get(value, &name.into())
+                .ok()
+                .and_then(|v| v.as_string())
+                .filter(|v| !v.is_empty())
+        };
+        let title = field("title");
+        let text = field("text")
+            .or_else(|| title.clone())
+            .ok_or("every outline needs a text or title")?;
+
+        let children = Reflect::get(value, &"children".into()).unwrap_or(JsValue::UNDEFINED);
+        let children = if Array::is_array(&children) {
+            Array::from(&children)
+                .iter()
+                .map(|child| Outline::from_js(&child, depth + 1))
+                .collect::<Result<_, _>>()?
+        } else {
+            Vec::new()
+        };
+
+        let xml_url = field("xml_url");
+        Ok(Self {
+            text,
+            title,
+            kind: field("type").or_else(|| xml_url.as_ref().map(|_| "rss".to_string())),
+            xml_url,
+            html_url: field("html_url"),
+            children,
+        })
+    }
+
+    fn write(&self, out: &mut String, indent: usize) {
+        out.push_str(&"  ".repeat(indent));
+        out.push_str("<outline text=\"");
+        out.push_str(&escape_xml(&self.text));
+        out.push('"');
+        for (name, value) in [
+            ("title", &self.title),
+            ("type", &self.kind),
+            ("xmlUrl", &self.xml_url),
+            ("htmlUrl", &self.html_url),
+        ] {
+            if let Some(value) = value {
+                out.push_str(&format!(" {name}=\"{}\"", escape_xml(value)));
+            }
+        }
+
+        if self.children.is_empty() {
+            out.push_str("/>\n");
+            return;
+        }
+
+        out.push_str(">\n");
+        for child in &self.children {
+            child.write(out, indent + 1);
+        }
+        out.push_str(&"  ".repeat(indent));
+        out.push_str("</outline>\n");
+    }
+}
+
+fn write_opml(outlines: &[Outline], title: Option<&str>) -> String {
+    let mut out = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<opml version=\"2.0\">\n  <head>\n",
+    );
+    out.push_str(&format!(
+        "    <title>{}</title>\n",
+        escape_xml(title.unwrap_or("Subscriptions"))
+    ));
+    out.push_str("  </head>\n  <body>\n");
+    for outline in outlines {
+        outline.write(&mut out, 2);
+    }
+    out.push_str("  </body>\n</opml>\n");
+    out
+}
+
+fn parse_outlines(xml: &str) -> Result<Vec<Outline>, String> {
+    let mut tags = tags(xml);
+    if !tags.any(|t| t.name == "opml" && !t.closing) {
+        return Err("missing <opml> root element".to_string());
+    }
+    if !tags.any(|t| t.name == "body" && !t.closing) {
+        return Err("missing <body> element".to_string());
+    }
+
+    let mut roots = Vec::new();
+    let mut stack: Vec<Outline> = Vec::new();
+
+    let close = |stack: &mut Vec<Outline>, roots: &mut Vec<Outline>| {
+        if let Some(outline) = stack.pop() {
+            match stack.last_mut() {
+                Some(parent) => parent.children.push(outline),
+                None => roots.push(outline),
+            }
+        }
+    };
+
+    for tag in tags {
+        match (tag.name.as_str(), tag.closing) {
+            ("outline", false) => {
+                if stack.len() >= MAX_OUTLINE_DEPTH {
+                    return Err(format!(
+                        "outlines are nested deeper than {MAX_OUTLINE_DEPTH} levels"
+                    ));
+                }
+                stack.push(Outline::from_tag(&tag));
+                if tag.self_closing {
+                    close(&mut stack, &mut roots);
+                }
+            }
+            ("outline", true) => close(&mut stack, &mut roots),
+            ("body", true) => break,
+            _ => {}
+        }
+    }
+
+    // Unclosed outlines are closed implicitly at the end of the body.
+    while !stack.is_empty() {
+        close(&mut stack, &mut roots);
+    }
+
+    Ok(roots)
+}
+
+/// Finds RSS, Atom and JSON feeds advertised via `<link rel="alternate">` and
+/// returns `{url, type, title}` objects with hrefs resolved against `base_url`.
+/// Links without a type are accepted when the href ends in `.rss`, `.xml`,
+/// `.rdf` or `.atom`. Duplicates are dropped by canonical URL comparison.
+#[wasm_bindgen]
+pub fn discover_feed_urls(html: &str, base_url: &str) -> Result<Array, JsValue> {
+    let feeds = discover(html, base_url).map_err(|e| JsValue::from_str(&e))?;
+
+    feeds
+        .into_iter()
+        .map(|feed| {
+            let obj = Object::new();
+            Reflect::set(&obj, &"url".into(), &feed.url.into())?;
+            Reflect::set(&obj, &"type".into(), &feed.kind.into())?;
+            Reflect::set(
+                &obj,
+                &"title".into(),
+                &feed.title.map_or(JsValue::NULL, JsValue::from),
+            )?;
+            Ok(JsValue::from(obj))
+        })
+        .collect()
+}
+
+/// Parses an OPML document into nested `{text, title, type, xml_url, html_url,
+/// children}` outlines, preserving folder structure.
+#[wasm_bindgen]
+pub fn parse_opml(xml: &str) -> Result<Array, JsValue> {
+    let outlines = parse_outlines(xml).map_err(|e| JsValue::from_str(&e))?;
+    Ok(outlines.iter().map(Outline::to_js).collect())
+}
+
+/// Builds an OPML 2.0 document from outlines shaped like `parse_opml` output.
+/// Outlines with an `xml_url` but no `type` are written as `type="rss"`.
+#[wasm_bindgen]
+pub fn build_opml(feeds: JsValue, title: Option<String>) -> Result<String, JsValue> {
+    if !Array::is_array(&feeds) {
+        return Err(JsValue::from_str("feeds must be an array"));
+    }
+    let outlines = Array::from(&feeds)
+        .iter()
+        .map(|feed| Outline::from_js(&feed, 0))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| JsValue::from_str(&e))?;
+
+    Ok(write_opml(&outlines, title.as_deref()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BASE: &str = "https://example.com/blog/";
+
+    fn urls(html: &str) -> Vec<String> {
+        discover(html, BASE)
+            .unwrap()
+            .into_iter()
+            .map(|feed| feed.url)
+            .collect()
+    }
+
+    #[test]
+    fn discovers_typed_feeds_and_resolves_hrefs() {
+        let feeds = discover(
+            r#"<head>
+                <link rel="alternate" type="application/rss+xml" title=" Posts " href="feed.xml">
+                <link rel="alternate" type="application/atom+xml" href="/atom">
+                <link rel="alternate" type="application/feed+json" href="https://cdn.example.com/feed.json">
+                <link rel="stylesheet" href="style.css">
+                <link rel="alternate" hreflang="de" href="/de/">
+            </head>"#,
+            BASE,
+        )
+        .unwrap();
+
+        let found: Vec<_> = feeds
+            .iter()
+            .map(|f| (f.url.as_str(), f.kind, f.title.as_deref()))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                (
+                    "https://example.com/blog/feed.xml",
+                    "application/rss+xml",
+                    Some("Posts")
+                ),
+                ("https://example.com/atom", "application/atom+xml", None),
+                (
+                    "https://cdn.example.com/feed.json",
+                    "application/feed+json",
+                    None
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn tolerates_uppercase_tags_and_attributes() {
+        assert_eq!(
+            urls(r#"<LINK REL="Alternate" TYPE="Application/RSS+XML" HREF="/rss">"#),
+            ["https://example.com/rss"]
+        );
+    }
+
+    #[test]
+    fn falls_back_to_extension_without_type() {
+        assert_eq!(
+            urls(
+                r#"<link rel="alternate" href="/index.rss">
+                   <link rel="alternate" href="/feed.atom">
+                   <link rel="alternate" href="/page.html">"#
+            ),
+            [
+                "https://example.com/index.rss",
+                "https://example.com/feed.atom"
+            ]
+        );
+    }
+
+    #[test]
+    fn strips_mime_parameters() {
+        assert_eq!(
+            urls(r#"<link rel="alternate" type="application/rss+xml; charset=utf-8" href="/f">"#),
+            ["https://example.com/f"]
+        );
+    }
+
+    #[test]
+    fn unquoted_self_closing_href_drops_the_slash() {
+        assert_eq!(
+            urls("<link rel=alternate href=/feed.rss/>"),
+            ["https://example.com/feed.rss"]
+        );
+    }
+
+    #[test]
+    fn scans_multiple_head_sections() {
+        assert_eq!(
+            urls(
+                r#"<html><head><link rel="alternate" href="/a.rss"></head>
+                   <body><p>text</p></body>
+                   <head><link rel="alternate" href="/b.rss"></head></html>"#
+            ),
+            ["https://example.com/a.rss", "https://example.com/b.rss"]
+        );
+    }
+
+    #[test]
+    fn dedups_canonically_equal_feeds() {
+        assert_eq!(
+            urls(
+                r#"<link rel="alternate" href="/feed.xml">
+                   <link rel="alternate" href="https://EXAMPLE.com/feed.xml#latest">
+                   <link rel="alternate" type="application/rss+xml" href="../feed.xml">"#
+            ),
+            ["https://example.com/feed.xml"]
+        );
+    }
+
+    #[test]
+    fn skips_script_contents_and_stray_brackets() {
+        let html = r#"<script>if (a < b) { /* don't */ }</script><link rel="alternate" type="application/rss+xml" href="/feed.xml">"#;
+        assert_eq!(urls(html), ["https://example.com/feed.xml"]);
+
+        let html = r#"<STYLE>a[title='<link rel="alternate" href="/x.rss">'] {}</Style>
+                      <p>1 < 2 and an unclosed <a title="oops></p>
+                      <link rel="alternate" href="/y.rss">"#;
+        assert_eq!(urls(html), ["https://example.com/y.rss"]);
+    }
+
+    #[test]
+    fn finds_close_tags_in_any_case() {
+        assert_eq!(find_ignore_case("a</ScRiPt>", 0, "</script"), Some(1));
+        assert_eq!(find_ignore_case("</style></STYLE>", 1, "</style"), Some(8));
+        assert_eq!(find_ignore_case("é</script", 0, "</script"), Some(2));
+        assert_eq!(find_ignore_case("</scrip", 0, "</script"), None);
+
+        // Many scripts used to lowercase the rest of the page once each.
+        let mut html = "<script>x</SCRIPT>".repeat(20_000);
+        html.push_str(r#"<link rel="alternate" type="application/rss+xml" href="/feed.xml">"#);
+        assert_eq!(urls(&html), ["https://example.com/feed.xml"]);
+    }
+
+    #[test]
+    fn rejects_invalid_base_url() {
+        assert!(discover("", "not a url").is_err());
+    }
+
+    const NESTED: &str = r#"<?xml version="1.0"?>
+        <opml version="2.0">
+          <head><title>Mine</title></head>
+          <body>
+            <outline text="Tech" title="Tech">
+              <outline text="Rust &amp; Friends" type="rss" xmlUrl="https://a.com/feed" htmlUrl="https://a.com/"/>
+              <outline text="Deep">
+                <outline text="Inner" type="rss" xmlUrl="https://b.com/rss?x=1&amp;y=2"/>
+              </outline>
+            </outline>
+            <outline TEXT="Loose" XMLURL="https://c.com/atom" TYPE="rss"/>
+          </body>
+        </opml>"#;
+
+    #[test]
+    fn parses_nested_outlines() {
+        let outlines = parse_outlines(NESTED).unwrap();
+        assert_eq!(outlines.len(), 2);
+
+        let tech = &outlines[0];
+        assert_eq!(tech.text, "Tech");
+        assert_eq!(tech.children.len(), 2);
+        assert_eq!(tech.children[0].text, "Rust & Friends");
+        assert_eq!(tech.children[0].html_url.as_deref(), Some("https://a.com/"));
+        assert_eq!(
+            tech.children[1].children[0].xml_url.as_deref(),
+            Some("https://b.com/rss?x=1&y=2")
+        );
+        assert_eq!(outlines[1].xml_url.as_deref(), Some("https://c.com/atom"));
+    }
+
+    #[test]
+    fn round_trips_nested_folders() {
+        let outlines = parse_outlines(NESTED).unwrap();
+        let xml = write_opml(&outlines, Some("Export & Co"));
+        assert!(xml.contains("<title>Export &amp; Co</title>"));
+        assert!(xml.contains("text=\"Rust &amp; Friends\""));
+        assert_eq!(parse_outlines(&xml).unwrap(), outlines);
+    }
+
+    #[test]
+    fn requires_opml_and_body() {
+        assert!(parse_outlines("<html><body></body></html>").is_err());
+        assert!(parse_outlines("<opml><head></head></opml>").is_err());
+    }
+
+    #[test]
+    fn limits_outline_depth() {
+        let nested = |depth: usize| {
+            format!(
+                "<opml><body>{}{}</body></opml>",
+                "<outline text=\"x\">".repeat(depth),
+                "</outline>".repeat(depth)
+            )
+        };
+        assert!(parse_outlines(&nested(MAX_OUTLINE_DEPTH)).is_ok());
+        assert!(parse_outlines(&nested(MAX_OUTLINE_DEPTH + 1)).is_err());
+    }
+}
@@ -0,0 +1,410 @@
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+
+use js_sys::{Array, Object, Reflect};
+use wasm_bindgen::prelude::*;
+
+use crate::core::{LinkInfo, LinkKind, analyze};
+
+const DEFAULT_MAX_PRELOAD: usize = 3;
+const DEFAULT_MAX_PREFETCH: usize = 5;
+const DEFAULT_MAX_PRECONNECT: usize = 4;
+
+struct Link {
+    href: String,
+    weight: f64,
+    position: f64,
+    destination: Option<String>,
+}
+
+struct Options {
+    max_preload: usize,
+    max_prefetch: usize,
+    max_preconnect: usize,
+    origin_denylist: HashSet<String>,
+    host_denylist: HashSet<String>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            max_preload: DEFAULT_MAX_PRELOAD,
+            max_prefetch: DEFAULT_MAX_PREFETCH,
+            max_preconnect: DEFAULT_MAX_PRECONNECT,
+            origin_denylist: HashSet::new(),
+            host_denylist: HashSet::new(),
+        }
+    }
+}
+
+#[derive(Default)]
+struct Plan {
+    preload: Vec<(String, String)>,
+    prefetch: Vec<String>,
+    preconnect_origins: Vec<String>,
+    ignore: Vec<String>,
+}
+
+fn origin_of(info: &LinkInfo) -> Option<String> {
+    Some(format!("{}//{}", info.protocol()?, info.host()?))
+}
+
+fn without_fragment(url: &str) -> &str {
+    url.split('#').next().unwrap_or(url)
+}
+
+/// Higher weight first, then earlier position, then input order.
+fn rank((index_a, a): (usize, &Link), (index_b, b): (usize, &Link)) -> Ordering {
+    b.weight
+        .total_cmp(&a.weight)
+        .then(a.position.total_cmp(&b.position))
+        .then(index_a.cmp(&index_b))
+}
+
+fn plan(links: &[Link], current_url: &str, options: &Options) -> Result<Plan, String> {
+    let current = analyze(current_url, None)?;
+    let is_http = matches!(current.protocol().as_deref(), Some("http:" | "https:"));
+    if !is_http || current.host().is_none() {
+        return Err("current_url must be an absolute http(s) URL".to_string());
+    }
+
+    let mut plan = Plan::default();
+    let mut seen_urls = HashSet::new();
+    let mut preload = Vec::new();
+    let mut prefetch = Vec::new();
+    let mut origin_counts: HashMap<String, (usize, usize)> = HashMap::new();
+
+    for (index, link) in links.iter().enumerate() {
+        let info = match analyze(&link.href, Some(current_url)) {
+            Ok(info) => info,
+            Err(_) => {
+                plan.ignore.push(link.href.clone());
+                continue;
+            }
+        };
+        let origin = origin_of(&info);
+
+        let denied = origin
+            .as_ref()
+            .is_some_and(|o| options.origin_denylist.contains(o))
+            || info
+                .host()
+                .is_some_and(|h| options.host_denylist.contains(&h));
+        let is_navigable = matches!(info.kind(), LinkKind::Internal | LinkKind::External);
+        if denied || !is_navigable {
+            plan.ignore.push(link.href.clone());
+            continue;
+        }
+
+        let normalized = info.normalized();
+        let target = without_fragment(&normalized).to_string();
+        if target == without_fragment(&current.normalized()) || !seen_urls.insert(target.clone()) {
+            plan.ignore.push(link.href.clone());
+            continue;
+        }
+
+        match (info.kind(), &link.destination) {
+            (LinkKind::Internal, Some(destination)) => {
+                preload.push((index, link, target, destination.clone()))
+            }
+            (LinkKind::Internal, None) if !info.is_downloadable() => {
+                prefetch.push((index, link, target))
+            }
+            (LinkKind::External, Some(_)) => {
+                let Some(origin) = origin else { continue };
+                let entry = origin_counts.entry(origin).or_insert((0, index));
+                entry.0 += 1;
+            }
+            _ => plan.ignore.push(link.href.clone()),
+        }
+    }
+
+    preload.sort_by(|a, b| rank((a.0, a.1), (b.0, b.1)));
+    for (i, (_, link, target, destination)) in preload.into_iter().enumerate() {
+        if i < options.max_preload {
+            plan.preload.push((target, destination));
+        } else {
+            plan.ignore.push(link.href.clone());
+        }
+    }
+
+    prefetch.sort_by(|a, b| rank((a.0, a.1), (b.0, b.1)));
+    for (i, (_, link, target)) in prefetch.into_iter().enumerate() {
+        if i < options.max_prefetch {
+            plan.prefetch.push(target);
+        } else {
+            plan.ignore.push(link.href.clone());
+        }
+    }
+
+    let mut origins: Vec<(String, (usize, usize))> = origin_counts.into_iter().collect();
+    origins.sort_by(|(_, (count_a, first_a)), (_, (count_b, first_b))| {
+        count_b.cmp(count_a).then(first_a.cmp(first_b))
+    });
+    plan.preconnect_origins = origins
+        .into_iter()
+        .take(options.max_preconnect)
+        .map(|(origin, _)| origin)
+        .collect();
+
+    Ok(plan)
+}
+
+fn read_link(value: &JsValue, index: usize) -> Result<Link, String> {
+    if let Some(href) = value.as_string() {
+        return Ok(Link {
+            href,
+            weight: 0.0,
+            position: index as f64,
+            destination: None,
+        });
+    }
+
+    let field = |name: &str| Reflect::get(value, &name.into()).unwrap_or(JsValue::UNDEFINED);
+    let href = field("href")
+        .as_string()
+        .ok_or_else(|| format!("link {index}: href must be a string"))?;
+
+    Ok(Link {
+        href,
+        weight: field("weight").as_f64().unwrap_or(0.0),
+        position: field("position").as_f64().unwrap_or(index as f64),
+        destination: field("as").as_string().filter(|d| !d.is_empty()),
+    })
+}
+
+fn read_options(value: &JsValue) -> Result<Options, String> {
+    let mut options = Options::default();
+    if value.is_null() || value.is_undefined() {
+        return Ok(options);
+    }
+
+    let field = |name: &str| Reflect::get(value, &name.into()).unwrap_or(JsValue::UNDEFINED);
+    let count = |name: &str, default: usize| match field(name) {
+        v if v.is_undefined() || v.is_null() => Ok(default),
+        v => v
+            .as_f64()
+            .filter(|n| *n >= 0.0 && n.fract() == 0.0)
+            .map(|n| n as usize)
+            .ok_or_else(|| format!("{name} must be a non-negative integer")),
+    };
+
+    options.max_preload = count("max_preload", DEFAULT_MAX_PRELOAD)?;
+    options.max_prefetch = count("max_prefetch", DEFAULT_MAX_PREFETCH)?;
+    options.max_preconnect = count("max_preconnect", DEFAULT_MAX_PRECONNECT)?;
+
+    let denylist = field("origin_denylist");
+    if Array::is_array(&denylist) {
+        for entry in Array::from(&denylist).iter().filter_map(|v| v.as_string()) {
+            deny(&mut options, &entry)?;
+        }
+    }
+
+    Ok(options)
+}
+
+/// Adds a denylist entry: an origin such as "https://cdn.example" blocks that
+/// scheme only, a bare host such as "cdn.example" blocks it on any scheme.
+fn deny(options: &mut Options, entry: &str) -> Result<(), String> {
+    let invalid = || format!("origin_denylist: '{entry}' is not an origin or host");
+    let entry = entry.trim();
+
+    // Normalize through the same path as the links so "HTTPS://CDN.example/" matches.
+    if entry.contains("://") {
+        let info = analyze(entry, None).map_err(|e| format!("origin_denylist: {e}"))?;
+        let origin = origin_of(&info).ok_or_else(invalid)?;
+        options.origin_denylist.insert(origin);
+    } else {
+        if entry.is_empty() || entry.contains(['/', '?', '#', '@']) {
+            return Err(invalid());
+        }
+        let info = analyze(&format!("http://{entry}"), None).map_err(|_| invalid())?;
+        options
+            .host_denylist
+            .insert(info.host().ok_or_else(invalid)?);
+    }
+    Ok(())
+}
+
+fn strings(values: &[String]) -> Array {
+    values.iter().map(JsValue::from).collect()
+}
+
+/// Decides which discovered links to preload, prefetch or preconnect to.
+///
+/// `links` holds hrefs or `{href, weight?, position?, as?}` objects. Links with
+/// an `as` destination are assets: same-origin assets are preloaded and
+/// cross-origin ones contribute their origin to `preconnect_origins`, ranked by
+/// frequency. Same-origin navigations are prefetched, ranked by weight and then
+/// position. Anchors, mailto/tel/javascript links, downloads, cross-origin
+/// navigations, denylisted origins, duplicates and links over the preload or
+/// prefetch caps end up in `ignore`.
+///
+/// `options` may set `max_preload` (3), `max_prefetch` (5), `max_preconnect`
+/// (4) and `origin_denylist`, whose entries are origins (`https://cdn.example`)
+/// or bare hosts (`cdn.example`, any scheme).
+#[wasm_bindgen]
+pub fn plan_link_hints(
+    links: Array,
+    current_url: &str,
+    options: JsValue,
+) -> Result<JsValue, JsValue> {
+    let links = links
+        .iter()
+        .enumerate()
+        .map(|(index, value)| read_link(&value, index))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| JsValue::from_str(&e))?;
+    let options = read_options(&options).map_err(|e| JsValue::from_str(&e))?;
+    let plan = plan(&links, current_url, &options).map_err(|e| JsValue::from_str(&e))?;
+
+    let preload: Array = plan
+        .preload
+        .iter()
+        .map(|(href, destination)| {
+            let obj = Object::new();
+            let _ = Reflect::set(&obj, &"href".into(), &href.into());
+            let _ = Reflect::set(&obj, &"as".into(), &destination.into());
+            JsValue::from(obj)
+        })
+        .collect();
+
+    let obj = Object::new();
+    Reflect::set(&obj, &"preload".into(), &preload)?;
+    Reflect::set(&obj, &"prefetch".into(), &strings(&plan.prefetch))?;
+    Reflect::set(
+        &obj,
+        &"preconnect_origins".into(),
+        &strings(&plan.preconnect_origins),
+    )?;
+    Reflect::set(&obj, &"ignore".into(), &strings(&plan.ignore))?;
+    Ok(obj.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CURRENT: &str = "https://example.com/docs/";
+
+    fn link(href: &str, destination: Option<&str>) -> Link {
+        Link {
+            href: href.to_string(),
+            weight: 0.0,
+            position: 0.0,
+            destination: destination.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn requires_http_current_url() {
+        let options = Options::default();
+        assert!(plan(&[], "https://example.com/", &options).is_ok());
+        assert!(plan(&[], "ftp://example.com/", &options).is_err());
+        assert!(plan(&[], "/relative", &options).is_err());
+    }
+
+    #[test]
+    fn ranks_by_weight_then_position() {
+        let links = [
+            Link {
+                weight: 1.0,
+                position: 5.0,
+                ..link("/a", None)
+            },
+            Link {
+                weight: 3.0,
+                position: 9.0,
+                ..link("/b", None)
+            },
+            Link {
+                weight: 1.0,
+                position: 2.0,
+                ..link("/c", None)
+            },
+        ];
+        let options = Options {
+            max_prefetch: 2,
+            ..Options::default()
+        };
+        let plan = plan(&links, CURRENT, &options).unwrap();
+        assert_eq!(
+            plan.prefetch,
+            ["https://example.com/b", "https://example.com/c"]
+        );
+        assert_eq!(plan.ignore, ["/a"]);
+    }
+
+    #[test]
+    fn denylist_accepts_origins_and_bare_hosts() {
+        let mut options = Options::default();
+        deny(&mut options, "HTTPS://CDN.one.com/").unwrap();
+        deny(&mut options, "cdn.two.com").unwrap();
+        assert!(deny(&mut options, "cdn.two.com/path").is_err());
+        assert!(deny(&mut options, "").is_err());
+
+        let links = [
+            link("https://cdn.one.com/a.js", Some("script")),
+            link("http://cdn.one.com/a.js", Some("script")),
+            link("https://cdn.two.com/b.js", Some("script")),
+            link("http://cdn.two.com/b.js", Some("script")),
+        ];
+        let plan = plan(&links, CURRENT, &options).unwrap();
+        assert_eq!(plan.preconnect_origins, ["http://cdn.one.com"]);
+        assert_eq!(plan.ignore.len(), 3);
+    }
+
+    #[test]
+    fn caps_and_dedups_500_mixed_links() {
+        let mut links = Vec::new();
+        for i in 0..500 {
+            let href = match i % 10 {
+                0 => format!("/page/{}", i % 40),
+                1 => format!("/page/{}#section", i % 40),
+                2 => format!("/static/app{}.js", i % 7),
+                3 => format!("https://cdn{}.example.net/lib.js?v={i}", i / 10 % 6),
+                4 => format!("https://other{}.example.org/", i % 3),
+                5 => "#top".to_string(),
+                6 => format!("mailto:user{i}@example.com"),
+                7 => format!("/files/report{i}.pdf"),
+                8 => CURRENT.to_string(),
+                _ => format!("javascript:void({i})"),
+            };
+            let destination = match i % 10 {
+                2 | 3 => Some("script"),
+                _ => None,
+            };
+            links.push(Link {
+                weight: (i % 13) as f64,
+                position: i as f64,
+                ..link(&href, destination)
+            });
+        }
+
+        let plan = plan(&links, CURRENT, &Options::default()).unwrap();
+        assert_eq!(plan.preload.len(), DEFAULT_MAX_PRELOAD);
+        assert_eq!(plan.prefetch.len(), DEFAULT_MAX_PREFETCH);
+        assert_eq!(plan.preconnect_origins.len(), DEFAULT_MAX_PRECONNECT);
+
+        let mut planned: Vec<&str> = plan.prefetch.iter().map(String::as_str).collect();
+        planned.extend(plan.preload.iter().map(|(href, _)| href.as_str()));
+        let unique: HashSet<&str> = planned.iter().copied().collect();
+        assert_eq!(unique.len(), planned.len());
+        assert!(planned.iter().all(|href| !href.contains('#')));
+
+        let origins: HashSet<&String> = plan.preconnect_origins.iter().collect();
+        assert_eq!(origins.len(), plan.preconnect_origins.len());
+        assert!(
+            plan.preconnect_origins
+                .iter()
+                .all(|o| o.starts_with("https://cdn"))
+        );
+
+        let planned_count = plan.preload.len() + plan.prefetch.len();
+        let external_assets = links.iter().filter(|l| l.href.contains("cdn")).count();
+        assert_eq!(
+            planned_count + external_assets + plan.ignore.len(),
+            links.len()
+        );
+    }
+}
@@ -1,6 +1,54 @@
 use wasm_bindgen::prelude::*;
 
+mod a11y;
+mod build_info;
+mod core;
+mod feeds;
+mod hints;
+mod nav_stack;
+mod srcset;
+mod url_utils;
+
+pub use crate::core::{LinkInfo, LinkKind, analyze_link};
+pub use a11y::{check_alt_text, check_link_text, duplicate_link_texts};
+pub use build_info::{get_build_info, get_enabled_features, health_check};
+pub use feeds::{build_opml, discover_feed_urls, parse_opml};
+pub use hints::plan_link_hints;
+pub use nav_stack::NavStack;
+pub use srcset::{build_srcset, parse_srcset, pick_srcset_candidate};
+pub use url_utils::{
+    ApiUrlBuilder, UrlMatcher, UrlPattern, build_google_calendar_link,
+    build_google_calendar_link_detailed, build_google_maps_link, build_hreflang_alternates,
+    build_mailto, build_path_from_pattern, build_tweet_intent, build_whatsapp_link,
+    compare_url_sets, compile_url_pattern, count_urls_by_scheme, decode_state_from_fragment,
+    dedupe_urls, display_domain, display_url, encode_state_to_fragment, encoded_url_length,
+    estimate_multipart_size, extract_locale_from_path, fits_url_budget, form_urldecode,
+    form_urlencode, fragment_budget_remaining, get_url_authority, get_url_origin, get_url_password,
+    get_url_username, group_urls_by_domain, match_url_pattern, next_page_url, page_bounds,
+    page_window, parse_query_typed, pick_canonical, prev_page_url, resolve_redirect_chain,
+    set_locale_in_path, state_fragment_size, strip_credentials, total_pages,
+    truncate_query_to_budget, truncate_query_to_budget_detailed, url_network_target,
+    url_targets_private_network, validate_bcp47, with_page_param,
+};
+
+/// Kept for existing callers: a plain, case-sensitive `http://`/`https://`
+/// prefix check. Use `analyze_link` for protocol-relative or unnormalized hrefs.
 #[wasm_bindgen]
 pub fn is_external(href: &str) -> bool {
     href.starts_with("http://") || href.starts_with("https://")
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn is_external_keeps_prefix_semantics() {
+        assert!(is_external("https://a.com"));
+        assert!(is_external("http://"));
+        assert!(!is_external("//cdn.example.com/x"));
+        assert!(!is_external("HTTP://EXAMPLE.COM"));
+        assert!(!is_external(" https://a.com"));
+        assert!(!is_external("/about"));
+    }
+}
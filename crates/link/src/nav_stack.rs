@@ -0,0 +1,254 @@
+use js_sys::{Array, JSON, Object, Reflect};
+use wasm_bindgen::prelude::*;
+
+use crate::url_utils::canonical_key;
+
+const DEFAULT_MAX_DEPTH: usize = 100;
+
+/// Back/forward history for embedded webviews, comparing entries canonically
+/// so fragment-only and trailing-slash differences don't create new entries.
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct NavStack {
+    entries: Vec<String>,
+    index: usize,
+    max_depth: usize,
+    ignore_fragments: bool,
+}
+
+impl NavStack {
+    fn with_options(max_depth: usize, ignore_fragments: bool) -> Result<Self, String> {
+        if max_depth == 0 {
+            return Err("max_depth must be greater than zero".to_string());
+        }
+
+        Ok(Self {
+            entries: Vec::new(),
+            index: 0,
+            max_depth,
+            ignore_fragments,
+        })
+    }
+
+    fn same_as_current(&self, url: &str) -> bool {
+        self.entries.get(self.index).is_some_and(|current| {
+            canonical_key(current, self.ignore_fragments)
+                == canonical_key(url, self.ignore_fragments)
+        })
+    }
+}
+
+#[wasm_bindgen]
+impl NavStack {
+    /// Creates an empty stack holding at most `max_depth` entries (default 100).
+    /// Fragments are ignored when comparing entries unless `ignore_fragments`
+    /// is `false`.
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        max_depth: Option<usize>,
+        ignore_fragments: Option<bool>,
+    ) -> Result<NavStack, JsValue> {
+        Self::with_options(
+            max_depth.unwrap_or(DEFAULT_MAX_DEPTH),
+            ignore_fragments.unwrap_or(true),
+        )
+        .map_err(|e| JsValue::from_str(&e))
+    }
+
+    /// Navigates to `url`, dropping any forward history. Does nothing when `url`
+    /// is canonically equal to the current entry. The oldest entry is evicted
+    /// once the stack is full.
+    pub fn push(&mut self, url: &str) {
+        if self.same_as_current(url) {
+            return;
+        }
+
+        if !self.entries.is_empty() {
+            self.entries.truncate(self.index + 1);
+        }
+        self.entries.push(url.to_string());
+        if self.entries.len() > self.max_depth {
+            self.entries.remove(0);
+        }
+        self.index = self.entries.len() - 1;
+    }
+
+    /// Replaces the current entry without touching the rest of the history.
+    pub fn replace(&mut self, url: &str) {
+        match self.entries.get_mut(self.index) {
+            Some(current) => *current = url.to_string(),
+            None => self.push(url),
+        }
+    }
+
+    pub fn back(&mut self) -> Option<String> {
+        if !self.can_go_back() {
+            return None;
+        }
+        self.index -= 1;
+        self.current()
+    }
+
+    pub fn forward(&mut self) -> Option<String> {
+        if !self.can_go_forward() {
+            return None;
+        }
+        self.index += 1;
+        self.current()
+    }
+
+    pub fn current(&self) -> Option<String> {
+        self.entries.get(self.index).cloned()
+    }
+
+    pub fn can_go_back(&self) -> bool {
+        !self.entries.is_empty() && self.index > 0
+    }
+
+    pub fn can_go_forward(&self) -> bool {
+        self.index + 1 < self.entries.len()
+    }
+
+    pub fn entries(&self) -> Array {
+        self.entries.iter().map(JsValue::from).collect()
+    }
+
+    /// Serializes the stack, including its position and options, so it can be
+    /// restored with `from_json`.
+    pub fn to_json(&self) -> Result<String, JsValue> {
+        let obj = Object::new();
+        Reflect::set(&obj, &"entries".into(), &self.entries())?;
+        Reflect::set(&obj, &"index".into(), &(self.index as u32).into())?;
+        Reflect::set(&obj, &"max_depth".into(), &(self.max_depth as u32).into())?;
+        Reflect::set(
+            &obj,
+            &"ignore_fragments".into(),
+            &self.ignore_fragments.into(),
+        )?;
+
+        JSON::stringify(&obj)?
+            .as_string()
+            .ok_or_else(|| JsValue::from_str("failed to serialize navigation stack"))
+    }
+
+    pub fn from_json(json: &str) -> Result<NavStack, JsValue> {
+        let value = JSON::parse(json)?;
+        let invalid =
+            |field: &str| JsValue::from_str(&format!("invalid navigation stack: bad '{field}'"));
+        let field = |name: &str| Reflect::get(&value, &name.into()).unwrap_or(JsValue::UNDEFINED);
+
+        let max_depth = field("max_depth")
+            .as_f64()
+            .filter(|n| *n >= 1.0 && n.fract() == 0.0)
+            .ok_or_else(|| invalid("max_depth"))? as usize;
+        let ignore_fragments = field("ignore_fragments")
+            .as_bool()
+            .ok_or_else(|| invalid("ignore_fragments"))?;
+
+        let entries = field("entries");
+        if !Array::is_array(&entries) {
+            return Err(invalid("entries"));
+        }
+        let entries = Array::from(&entries)
+            .iter()
+            .map(|entry| entry.as_string())
+            .collect::<Option<Vec<_>>>()
+            .ok_or_else(|| invalid("entries"))?;
+
+        let index = field("index")
+            .as_f64()
+            .filter(|n| *n >= 0.0 && n.fract() == 0.0)
+            .map(|n| n as usize)
+            .filter(|n| *n < entries.len().max(1))
+            .ok_or_else(|| invalid("index"))?;
+
+        if entries.len() > max_depth {
+            return Err(invalid("entries"));
+        }
+
+        Ok(NavStack {
+            entries,
+            index,
+            max_depth,
+            ignore_fragments,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stack(max_depth: usize, ignore_fragments: bool) -> NavStack {
+        NavStack::with_options(max_depth, ignore_fragments).unwrap()
+    }
+
+    #[test]
+    fn canonical_key_normalizes_trailing_slash_and_fragment() {
+        assert_eq!(
+            canonical_key("https://a.com/docs/", true),
+            canonical_key("https://a.com/docs#intro", true)
+        );
+        assert_ne!(
+            canonical_key("https://a.com/docs#intro", false),
+            canonical_key("https://a.com/docs", false)
+        );
+        assert_eq!(canonical_key("/docs/?q=1#top", true), "/docs?q=1");
+        assert_eq!(canonical_key("/", true), "/");
+    }
+
+    #[test]
+    fn push_after_back_truncates_forward_history() {
+        let mut nav = stack(10, true);
+        nav.push("https://a.com/1");
+        nav.push("https://a.com/2");
+        nav.push("https://a.com/3");
+        assert_eq!(nav.back().as_deref(), Some("https://a.com/2"));
+        assert_eq!(nav.back().as_deref(), Some("https://a.com/1"));
+        assert!(nav.can_go_forward());
+
+        nav.push("https://a.com/4");
+        assert!(!nav.can_go_forward());
+        assert_eq!(nav.forward(), None);
+        assert_eq!(nav.entries, ["https://a.com/1", "https://a.com/4"]);
+        assert_eq!(nav.back().as_deref(), Some("https://a.com/1"));
+        assert_eq!(nav.back(), None);
+    }
+
+    #[test]
+    fn fragment_only_navigation_is_not_a_new_entry() {
+        let mut nav = stack(10, true);
+        nav.push("https://a.com/page");
+        nav.push("https://a.com/page#section");
+        nav.push("https://a.com/page/");
+        assert_eq!(nav.entries.len(), 1);
+        assert!(!nav.can_go_back());
+
+        let mut nav = stack(10, false);
+        nav.push("https://a.com/page");
+        nav.push("https://a.com/page#section");
+        assert_eq!(nav.entries.len(), 2);
+        assert_eq!(nav.back().as_deref(), Some("https://a.com/page"));
+    }
+
+    #[test]
+    fn evicts_oldest_entry_at_max_depth() {
+        let mut nav = stack(3, true);
+        for i in 1..=5 {
+            nav.push(&format!("https://a.com/{i}"));
+        }
+        assert_eq!(
+            nav.entries,
+            ["https://a.com/3", "https://a.com/4", "https://a.com/5"]
+        );
+        assert_eq!(nav.current().as_deref(), Some("https://a.com/5"));
+        assert_eq!(nav.back().as_deref(), Some("https://a.com/4"));
+        assert_eq!(nav.back().as_deref(), Some("https://a.com/3"));
+        assert_eq!(nav.back(), None);
+    }
+
+    #[test]
+    fn rejects_zero_max_depth() {
+        assert!(NavStack::with_options(0, true).is_err());
+    }
+}
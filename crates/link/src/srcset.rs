@@ -0,0 +1,479 @@
+use std::collections::HashSet;
+
+use js_sys::{Array, Object, Reflect};
+use wasm_bindgen::prelude::*;
+
+struct Candidate {
+    url: String,
+    width: Option<u32>,
+    density: Option<f64>,
+}
+
+impl Candidate {
+    fn to_js(&self) -> Result<JsValue, JsValue> {
+        let obj = Object::new();
+        Reflect::set(&obj, &"url".into(), &self.url.as_str().into())?;
+        Reflect::set(
+            &obj,
+            &"width".into(),
+            &self.width.map_or(JsValue::NULL, JsValue::from),
+        )?;
+        Reflect::set(
+            &obj,
+            &"density".into(),
+            &self.density.map_or(JsValue::NULL, JsValue::from),
+        )?;
+        Ok(obj.into())
+    }
+
+    fn from_js(value: &JsValue, index: u32) -> Result<Self, String> {
+        let field = |name: &str| Reflect::get(value, &name.into()).unwrap_or(JsValue::UNDEFINED);
+
+        let url = field("url")
+            .as_string()
+            .ok_or_else(|| format!("candidate {index}: url must be a string"))?;
+        let width = match field("width") {
+            v if v.is_null() || v.is_undefined() => None,
+            v => match v.as_f64() {
+                Some(w) if w >= 1.0 && w.fract() == 0.0 && w <= u32::MAX as f64 => Some(w as u32),
+                _ => {
+                    return Err(format!(
+                        "candidate {index}: width must be a positive integer"
+                    ));
+                }
+            },
+        };
+        let density = match field("density") {
+            v if v.is_null() || v.is_undefined() => None,
+            v => match v.as_f64() {
+                Some(d) if is_valid_density(d) => Some(d),
+                _ => {
+                    return Err(format!(
+                        "candidate {index}: density must be a positive number"
+                    ));
+                }
+            },
+        };
+
+        Ok(Self {
+            url,
+            width,
+            density,
+        })
+    }
+
+    fn descriptor(&self, index: u32) -> Result<Option<String>, String> {
+        if self.url.is_empty() {
+            return Err(format!("candidate {index}: url is empty"));
+        }
+        if self.url.chars().any(is_ascii_whitespace) {
+            return Err(format!("candidate {index}: url contains whitespace"));
+        }
+        if self.url.starts_with(',') || self.url.ends_with(',') {
+            return Err(format!(
+                "candidate {index}: url starts or ends with a comma"
+            ));
+        }
+
+        match (self.width, self.density) {
+            (Some(_), Some(_)) => Err(format!("candidate {index}: has both a width and a density")),
+            (Some(w), None) => Ok(Some(format!("{w}w"))),
+            (None, Some(d)) => Ok(Some(format!("{d}x"))),
+            (None, None) => Ok(None),
+        }
+    }
+
+    /// The descriptor value that must be unique within a srcset; a candidate
+    /// without one counts as `1x`.
+    fn descriptor_key(&self) -> String {
+        match self.width {
+            Some(w) => format!("{w}w"),
+            None => format!("{}x", self.density.unwrap_or(1.0)),
+        }
+    }
+}
+
+/// Densities accepted by both `parse_srcset` and `build_srcset`, so a parsed
+/// srcset can always be built again. The HTML parsing algorithm lets `0x`
+/// through, but a zero density is non-conforming and useless for selection.
+fn is_valid_density(d: f64) -> bool {
+    d > 0.0 && d.is_finite()
+}
+
+fn is_ascii_whitespace(c: char) -> bool {
+    matches!(c, ' ' | '\t' | '\n' | '\x0c' | '\r')
+}
+
+/// Splits a srcset attribute into candidates following the HTML "parse a srcset
+/// attribute" algorithm, so commas inside URLs do not split a candidate.
+fn parse(input: &str) -> Result<Vec<Candidate>, String> {
+    let chars: Vec<char> = input.chars().collect();
+    let mut pos = 0;
+    let mut candidates = Vec::new();
+    let mut seen = HashSet::new();
+
+    loop {
+        while pos < chars.len() && (is_ascii_whitespace(chars[pos]) || chars[pos] == ',') {
+            pos += 1;
+        }
+        if pos >= chars.len() {
+            return Ok(candidates);
+        }
+
+        let start = pos;
+        while pos < chars.len() && !is_ascii_whitespace(chars[pos]) {
+            pos += 1;
+        }
+        let mut url: String = chars[start..pos].iter().collect();
+
+        let mut descriptors = Vec::new();
+        if url.ends_with(',') {
+            url = url.trim_end_matches(',').to_string();
+        } else {
+            pos = tokenize_descriptors(&chars, pos, &mut descriptors);
+        }
+
+        let candidate = parse_descriptors(url, &descriptors)?;
+        let key = candidate.descriptor_key();
+        if !seen.insert(key.clone()) {
+            return Err(format!(
+                "duplicate descriptor '{key}' for '{}': another candidate already uses it",
+                candidate.url
+            ));
+        }
+        candidates.push(candidate);
+    }
+}
+
+fn tokenize_descriptors(chars: &[char], mut pos: usize, descriptors: &mut Vec<String>) -> usize {
+    enum State {
+        InDescriptor,
+        InParens,
+        AfterDescriptor,
+    }
+
+    while pos < chars.len() && is_ascii_whitespace(chars[pos]) {
+        pos += 1;
+    }
+
+    let mut current = String::new();
+    let mut state = State::InDescriptor;
+
+    loop {
+        let Some(&c) = chars.get(pos) else {
+            if !current.is_empty() {
+                descriptors.push(current);
+            }
+            return pos;
+        };
+
+        match state {
+            State::InDescriptor => {
+                if is_ascii_whitespace(c) {
+                    if !current.is_empty() {
+                        descriptors.push(std::mem::take(&mut current));
+                        state = State::AfterDescriptor;
+                    }
+                } else if c == ',' {
+                    if !current.is_empty() {
+                        descriptors.push(current);
+                    }
+                    return pos + 1;
+                } else {
+                    if c == '(' {
+                        state = State::InParens;
+                    }
+                    current.push(c);
+                }
+            }
+            State::InParens => {
+                if c == ')' {
+                    state = State::InDescriptor;
+                }
+                current.push(c);
+            }
+            State::AfterDescriptor => {
+                if !is_ascii_whitespace(c) {
+                    state = State::InDescriptor;
+                    continue;
+                }
+            }
+        }
+
+        pos += 1;
+    }
+}
+
+fn parse_descriptors(url: String, descriptors: &[String]) -> Result<Candidate, String> {
+    let mut width = None;
+    let mut density = None;
+    let mut height = None;
+
+    for descriptor in descriptors {
+        let split = descriptor.char_indices().last().map_or(0, |(i, _)| i);
+        let (value, kind) = descriptor.split_at(split);
+        let invalid = || format!("invalid descriptor '{descriptor}' for '{url}'");
+        let duplicate = || format!("duplicate descriptor '{descriptor}' for '{url}'");
+
+        match kind {
+            "w" => {
+                if width.is_some() || density.is_some() {
+                    return Err(duplicate());
+                }
+                match parse_non_negative_integer(value) {
+                    Some(w) if w > 0 => width = Some(w),
+                    _ => return Err(invalid()),
+                }
+            }
+            "x" => {
+                if width.is_some() || density.is_some() || height.is_some() {
+                    return Err(duplicate());
+                }
+                match parse_float(value) {
+                    Some(d) if is_valid_density(d) => density = Some(d),
+                    _ => return Err(invalid()),
+                }
+            }
+            "h" => {
+                if height.is_some() || density.is_some() {
+                    return Err(duplicate());
+                }
+                match parse_non_negative_integer(value) {
+                    Some(h) if h > 0 => height = Some(h),
+                    _ => return Err(invalid()),
+                }
+            }
+            _ => return Err(invalid()),
+        }
+    }
+
+    if height.is_some() && width.is_none() {
+        return Err(format!("height descriptor without width for '{url}'"));
+    }
+    if width.is_none() && density.is_none() {
+        density = Some(1.0);
+    }
+
+    Ok(Candidate {
+        url,
+        width,
+        density,
+    })
+}
+
+fn parse_non_negative_integer(value: &str) -> Option<u32> {
+    if value.is_empty() || !value.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    value.parse().ok()
+}
+
+fn parse_float(value: &str) -> Option<f64> {
+    // Rust accepts forms like "inf", "+1", ".5" and "1." that the HTML number
+    // grammar does not: digits, optionally "." and digits, optionally an exponent.
+    fn digits(s: &str) -> Option<&str> {
+        let end = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+        (end > 0).then(|| &s[end..])
+    }
+
+    let mut rest = digits(value.strip_prefix('-').unwrap_or(value))?;
+    if let Some(fraction) = rest.strip_prefix('.') {
+        rest = digits(fraction)?;
+    }
+    if let Some(exponent) = rest.strip_prefix(['e', 'E']) {
+        rest = digits(exponent.strip_prefix(['-', '+']).unwrap_or(exponent))?;
+    }
+    if !rest.is_empty() {
+        return None;
+    }
+    value.parse::<f64>().ok().filter(|v| v.is_finite())
+}
+
+fn pick(candidates: &[Candidate], viewport_width: f64, dpr: f64) -> Option<&Candidate> {
+    // Without a sizes attribute the browser assumes the image spans 100vw.
+    let effective = |c: &Candidate| match c.width {
+        Some(w) => w as f64 / viewport_width,
+        None => c.density.unwrap_or(1.0),
+    };
+
+    let best_fit = candidates
+        .iter()
+        .filter(|c| effective(c) >= dpr)
+        .min_by(|a, b| effective(a).total_cmp(&effective(b)));
+
+    best_fit.or_else(|| {
+        candidates
+            .iter()
+            .max_by(|a, b| effective(a).total_cmp(&effective(b)))
+    })
+}
+
+/// Parses a srcset attribute into `{url, width, density}` objects. Exactly one
+/// of `width` and `density` is set; a candidate without a descriptor is `1x`.
+#[wasm_bindgen]
+pub fn parse_srcset(srcset: &str) -> Result<Array, JsValue> {
+    let candidates = parse(srcset).map_err(|e| JsValue::from_str(&e))?;
+    candidates.iter().map(Candidate::to_js).collect()
+}
+
+/// Builds a srcset attribute from `{url, width?, density?}` objects. Callers may
+/// mix width and density candidates, but no two may share a descriptor value.
+#[wasm_bindgen]
+pub fn build_srcset(candidates: JsValue) -> Result<String, JsValue> {
+    if !Array::is_array(&candidates) {
+        return Err(JsValue::from_str("candidates must be an array"));
+    }
+
+    let mut parts = Vec::new();
+    let mut seen = HashSet::new();
+    for (index, value) in Array::from(&candidates).iter().enumerate() {
+        let index = index as u32;
+        let candidate = Candidate::from_js(&value, index).map_err(|e| JsValue::from_str(&e))?;
+        let key = candidate.descriptor_key();
+        if !seen.insert(key.clone()) {
+            return Err(JsValue::from_str(&format!(
+                "candidate {index}: duplicate descriptor '{key}'"
+            )));
+        }
+        match candidate
+            .descriptor(index)
+            .map_err(|e| JsValue::from_str(&e))?
+        {
+            Some(descriptor) => parts.push(format!("{} {descriptor}", candidate.url)),
+            None => parts.push(candidate.url),
+        }
+    }
+
+    Ok(parts.join(", "))
+}
+
+/// Returns the URL a browser would pick for the given viewport width and device
+/// pixel ratio, assuming no `sizes` attribute (i.e. `100vw`).
+#[wasm_bindgen]
+pub fn pick_srcset_candidate(
+    srcset: &str,
+    viewport_width: f64,
+    dpr: f64,
+) -> Result<Option<String>, JsValue> {
+    if !(viewport_width > 0.0 && viewport_width.is_finite()) {
+        return Err(JsValue::from_str(
+            "viewport_width must be a positive number",
+        ));
+    }
+    if !(dpr > 0.0 && dpr.is_finite()) {
+        return Err(JsValue::from_str("dpr must be a positive number"));
+    }
+
+    let candidates = parse(srcset).map_err(|e| JsValue::from_str(&e))?;
+    Ok(pick(&candidates, viewport_width, dpr).map(|c| c.url.clone()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn urls(candidates: &[Candidate]) -> Vec<&str> {
+        candidates.iter().map(|c| c.url.as_str()).collect()
+    }
+
+    #[test]
+    fn parses_width_and_density_candidates() {
+        let candidates = parse("a.jpg 480w, b.jpg 800w 600h").unwrap();
+        assert_eq!(urls(&candidates), ["a.jpg", "b.jpg"]);
+        assert_eq!(candidates[0].width, Some(480));
+        assert_eq!(candidates[1].width, Some(800));
+
+        let candidates = parse("a.jpg, b.jpg 1.5x,c.jpg 2x").unwrap();
+        let densities: Vec<_> = candidates.iter().map(|c| c.density).collect();
+        assert_eq!(densities, [Some(1.0), Some(1.5), Some(2.0)]);
+    }
+
+    #[test]
+    fn keeps_commas_inside_urls() {
+        let candidates = parse("img.jpg?a=1,2 1x, data:image/png;base64,AAA= 2x").unwrap();
+        assert_eq!(
+            urls(&candidates),
+            ["img.jpg?a=1,2", "data:image/png;base64,AAA="]
+        );
+    }
+
+    #[test]
+    fn rejects_duplicate_descriptors_across_candidates() {
+        assert!(parse("a.jpg 1x, b.jpg 1x").is_err());
+        assert!(parse("a.jpg, b.jpg 1x").is_err());
+        assert!(parse("a.jpg 1x, b.jpg 1.0x").is_err());
+        assert!(parse("a.jpg 100w, b.jpg 100w").is_err());
+        assert!(parse("a.jpg 100w, b.jpg 1x").is_ok());
+    }
+
+    #[test]
+    fn rejects_invalid_descriptors() {
+        assert!(parse("a.jpg 1x 2x").is_err());
+        assert!(parse("a.jpg 0w").is_err());
+        assert!(parse("a.jpg 100h").is_err());
+        assert!(parse("a.jpg 1.x").is_err());
+        assert!(parse("a.jpg .5x").is_err());
+        assert!(parse("a.jpg +1x").is_err());
+        assert!(parse("a.jpg infx").is_err());
+        assert!(parse("a.jpg 0x").is_err());
+        assert!(parse("a.jpg 0.0e5x").is_err());
+        assert!(parse("a.jpg -1x").is_err());
+    }
+
+    #[test]
+    fn parsed_candidates_build_again() {
+        let srcset = "a.jpg, b.jpg 1.5x, c.jpg 0.5x, d.jpg 800w 600h, e.jpg 1e1x";
+        let candidates = parse(srcset).unwrap();
+        let rebuilt: Vec<String> = candidates
+            .iter()
+            .enumerate()
+            .map(|(index, c)| {
+                assert!(c.density.is_none_or(is_valid_density));
+                let descriptor = c.descriptor(index as u32).unwrap().unwrap();
+                format!("{} {descriptor}", c.url)
+            })
+            .collect();
+        assert_eq!(
+            rebuilt.join(", "),
+            "a.jpg 1x, b.jpg 1.5x, c.jpg 0.5x, d.jpg 800w, e.jpg 10x"
+        );
+        assert_eq!(parse(&rebuilt.join(", ")).unwrap().len(), 5);
+    }
+
+    #[test]
+    fn parse_float_follows_html_grammar() {
+        assert_eq!(parse_float("1"), Some(1.0));
+        assert_eq!(parse_float("1.25"), Some(1.25));
+        assert_eq!(parse_float("-2"), Some(-2.0));
+        assert_eq!(parse_float("1e2"), Some(100.0));
+        assert_eq!(parse_float("1.5E-1"), Some(0.15));
+        assert_eq!(parse_float("1."), None);
+        assert_eq!(parse_float("1e"), None);
+        assert_eq!(parse_float(""), None);
+        assert_eq!(parse_float("1x"), None);
+    }
+
+    #[test]
+    fn empty_srcset_has_no_candidates() {
+        assert!(parse("").unwrap().is_empty());
+        assert!(parse(" , ,").unwrap().is_empty());
+    }
+
+    #[test]
+    fn picks_smallest_sufficient_density() {
+        let candidates = parse("a.jpg 1x, b.jpg 2x, c.jpg 3x").unwrap();
+        assert_eq!(pick(&candidates, 400.0, 1.0).unwrap().url, "a.jpg");
+        assert_eq!(pick(&candidates, 400.0, 1.5).unwrap().url, "b.jpg");
+        assert_eq!(pick(&candidates, 400.0, 4.0).unwrap().url, "c.jpg");
+    }
+
+    #[test]
+    fn picks_width_candidates_relative_to_viewport() {
+        let candidates = parse("s.jpg 400w, m.jpg 800w, l.jpg 1600w").unwrap();
+        assert_eq!(pick(&candidates, 400.0, 1.0).unwrap().url, "s.jpg");
+        assert_eq!(pick(&candidates, 400.0, 2.0).unwrap().url, "m.jpg");
+        assert_eq!(pick(&candidates, 1000.0, 1.0).unwrap().url, "l.jpg");
+        assert_eq!(pick(&candidates, 2000.0, 1.0).unwrap().url, "l.jpg");
+        assert!(pick(&[], 400.0, 1.0).is_none());
+    }
+}
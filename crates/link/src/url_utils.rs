@@ -0,0 +1,301 @@
+use js_sys::Array;
+use url::{Host, Url};
+
+mod authority;
+mod budget;
+mod builder;
+mod compare;
+mod dedupe;
+mod display;
+mod form;
+mod locale;
+mod matcher;
+mod network;
+mod pagination;
+mod providers;
+mod query;
+mod redirects;
+mod route;
+mod state;
+
+pub use authority::{
+    get_url_authority, get_url_origin, get_url_password, get_url_username, strip_credentials,
+};
+pub use budget::{
+    encoded_url_length, fits_url_budget, fragment_budget_remaining, truncate_query_to_budget,
+    truncate_query_to_budget_detailed,
+};
+pub use builder::ApiUrlBuilder;
+pub use compare::compare_url_sets;
+pub use dedupe::{count_urls_by_scheme, dedupe_urls, group_urls_by_domain};
+pub use display::{display_domain, display_url};
+pub use form::{estimate_multipart_size, form_urldecode, form_urlencode};
+pub use locale::{
+    build_hreflang_alternates, extract_locale_from_path, set_locale_in_path, validate_bcp47,
+};
+pub use matcher::UrlMatcher;
+pub use network::{url_network_target, url_targets_private_network};
+pub use pagination::{
+    next_page_url, page_bounds, page_window, prev_page_url, total_pages, with_page_param,
+};
+pub use providers::{
+    build_google_calendar_link, build_google_calendar_link_detailed, build_google_maps_link,
+    build_mailto, build_tweet_intent, build_whatsapp_link,
+};
+pub use query::parse_query_typed;
+pub use redirects::{pick_canonical, resolve_redirect_chain};
+pub use route::{UrlPattern, build_path_from_pattern, compile_url_pattern, match_url_pattern};
+pub use state::{decode_state_from_fragment, encode_state_to_fragment, state_fragment_size};
+
+/// Suffixes under which registrations happen one level deeper than the TLD.
+/// A short excerpt of the public suffix list covering the suffixes we see in
+/// practice, so the bundle doesn't have to ship the full list.
+const MULTI_LABEL_SUFFIXES: &[&str] = &[
+    "ac.jp",
+    "ac.uk",
+    "ac.in",
+    "appspot.com",
+    "azurewebsites.net",
+    "blogspot.com",
+    "cloudfront.net",
+    "co.id",
+    "co.il",
+    "co.in",
+    "co.jp",
+    "co.kr",
+    "co.nz",
+    "co.uk",
+    "co.za",
+    "com.ar",
+    "com.au",
+    "com.br",
+    "com.cn",
+    "com.co",
+    "com.hk",
+    "com.mx",
+    "com.my",
+    "com.ph",
+    "com.sg",
+    "com.tr",
+    "com.tw",
+    "com.vn",
+    "edu.au",
+    "edu.cn",
+    "github.io",
+    "gitlab.io",
+    "gob.mx",
+    "gov.au",
+    "gov.br",
+    "gov.cn",
+    "gov.in",
+    "gov.uk",
+    "govt.nz",
+    "herokuapp.com",
+    "ltd.uk",
+    "me.uk",
+    "ne.jp",
+    "net.au",
+    "net.br",
+    "net.cn",
+    "net.in",
+    "net.nz",
+    "netlify.app",
+    "or.jp",
+    "or.kr",
+    "org.au",
+    "org.br",
+    "org.cn",
+    "org.in",
+    "org.nz",
+    "org.uk",
+    "org.za",
+    "pages.dev",
+    "plc.uk",
+    "vercel.app",
+];
+
+/// Which differences canonical comparison treats as insignificant.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Ignore {
+    pub(crate) fragment: bool,
+    pub(crate) query: bool,
+    pub(crate) trailing_slash: bool,
+}
+
+/// Serializes `url` for comparison. The url crate already lowercases the
+/// scheme and host, drops default ports and resolves dot segments; this also
+/// drops an empty `?` and whatever `ignore` asks for.
+pub(crate) fn canonical_url(url: &Url, ignore: Ignore) -> String {
+    canonical(url, ignore).to_string()
+}
+
+pub(crate) fn canonical(url: &Url, ignore: Ignore) -> Url {
+    let mut url = url.clone();
+    if ignore.fragment {
+        url.set_fragment(None);
+    }
+    if ignore.query || url.query() == Some("") {
+        url.set_query(None);
+    }
+    if ignore.trailing_slash && url.path().len() > 1 && url.path().ends_with('/') {
+        let path = url.path().trim_end_matches('/').to_string();
+        url.set_path(&path);
+    }
+    url
+}
+
+/// Comparison key for two hrefs: the canonical URL without the path's
+/// trailing slash and, optionally, without the fragment. Hrefs that do not
+/// parse as absolute URLs fall back to plain string handling.
+pub(crate) fn canonical_key(href: &str, ignore_fragments: bool) -> String {
+    let href = href.trim();
+
+    match Url::parse(href) {
+        Ok(url) => canonical_url(
+            &url,
+            Ignore {
+                fragment: ignore_fragments,
+                query: false,
+                trailing_slash: true,
+            },
+        ),
+        Err(_) => {
+            let (rest, fragment) = match href.split_once('#') {
+                Some((rest, fragment)) if !ignore_fragments => (rest, Some(fragment)),
+                Some((rest, _)) => (rest, None),
+                None => (href, None),
+            };
+            let (path, query) = rest
+                .split_once('?')
+                .map_or((rest, None), |(p, q)| (p, Some(q)));
+
+            let mut key = match path.trim_end_matches('/') {
+                "" if path.starts_with('/') => "/".to_string(),
+                trimmed => trimmed.to_string(),
+            };
+            if let Some(query) = query {
+                key.push('?');
+                key.push_str(query);
+            }
+            if let Some(fragment) = fragment {
+                key.push('#');
+                key.push_str(fragment);
+            }
+            key
+        }
+    }
+}
+
+/// The registrable domain ("eTLD+1") of `host`: `news.bbc.co.uk` becomes
+/// `bbc.co.uk`. IP addresses and single-label hosts are returned unchanged.
+pub(crate) fn registrable_domain(host: &Host<&str>) -> String {
+    let domain = match host {
+        Host::Domain(domain) => domain.trim_end_matches('.'),
+        Host::Ipv4(ip) => return ip.to_string(),
+        Host::Ipv6(ip) => return format!("[{ip}]"),
+    };
+
+    let labels: Vec<&str> = domain.split('.').collect();
+    let suffix_len = match labels.len() {
+        0..=2 => return domain.to_string(),
+        n if MULTI_LABEL_SUFFIXES.contains(&labels[n - 2..].join(".").as_str()) => 2,
+        _ => 1,
+    };
+    labels[labels.len() - suffix_len - 1..].join(".")
+}
+
+/// Percent-encodes everything but RFC 3986 unreserved characters, so `/`,
+/// `?`, `#` and `%` in a segment can't change the structure of the path.
+pub(crate) fn encode_segment(segment: &str) -> String {
+    let mut out = String::with_capacity(segment.len());
+    for byte in segment.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                out.push(byte as char)
+            }
+            _ => out.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    out
+}
+
+/// Decodes `%XX` sequences, leaving malformed ones as they are. Unlike form
+/// decoding, `+` stays a plus sign. `None` when the result isn't UTF-8.
+pub(crate) fn percent_decode(s: &str) -> Option<String> {
+    let bytes = s.as_bytes();
+    let hex = |b: u8| (b as char).to_digit(16);
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match (
+            bytes[i],
+            bytes.get(i + 1).copied().and_then(hex),
+            bytes.get(i + 2).copied().and_then(hex),
+        ) {
+            (b'%', Some(high), Some(low)) => {
+                out.push((high * 16 + low) as u8);
+                i += 3;
+            }
+            (byte, _, _) => {
+                out.push(byte);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8(out).ok()
+}
+
+/// Reads every entry of `values` as a string; non-strings become `None`.
+pub(crate) fn read_strings(values: &Array) -> Vec<Option<String>> {
+    values.iter().map(|value| value.as_string()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn domain(url: &str) -> String {
+        registrable_domain(&Url::parse(url).unwrap().host().unwrap())
+    }
+
+    #[test]
+    fn canonical_url_applies_ignore_options() {
+        let url = Url::parse("HTTPS://Example.COM:443/a/b/?#top").unwrap();
+        let keep_all = Ignore {
+            fragment: false,
+            query: false,
+            trailing_slash: false,
+        };
+        assert_eq!(
+            canonical_url(&url, keep_all),
+            "https://example.com/a/b/#top"
+        );
+
+        let url = Url::parse("https://example.com/a/?q=1#top").unwrap();
+        let ignore_all = Ignore {
+            fragment: true,
+            query: true,
+            trailing_slash: true,
+        };
+        assert_eq!(canonical_url(&url, ignore_all), "https://example.com/a");
+    }
+
+    #[test]
+    fn percent_decodes_utf8_only() {
+        assert_eq!(percent_decode("caf%C3%A9+x%2F").as_deref(), Some("café+x/"));
+        assert_eq!(percent_decode("100%").as_deref(), Some("100%"));
+        assert_eq!(percent_decode("%zz%4").as_deref(), Some("%zz%4"));
+        assert_eq!(percent_decode("%FF"), None);
+    }
+
+    #[test]
+    fn registrable_domain_handles_multi_label_suffixes() {
+        assert_eq!(domain("https://news.bbc.co.uk/x"), "bbc.co.uk");
+        assert_eq!(domain("https://a.b.example.com/"), "example.com");
+        assert_eq!(domain("https://example.com/"), "example.com");
+        assert_eq!(domain("https://me.github.io/"), "me.github.io");
+        assert_eq!(domain("http://localhost:8080/"), "localhost");
+        assert_eq!(domain("http://192.168.0.1/"), "192.168.0.1");
+        assert_eq!(domain("http://[::1]/"), "[::1]");
+    }
+}
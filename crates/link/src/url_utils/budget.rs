@@ -0,0 +1,175 @@
+use js_sys::{Array, Object, Reflect};
+use url::Url;
+use wasm_bindgen::prelude::*;
+
+fn parse(url: &str) -> Result<Url, String> {
+    Url::parse(url.trim()).map_err(|e| format!("invalid URL: {e}"))
+}
+
+/// Length of the serialized URL. The url crate percent-encodes non-ASCII in
+/// the path, query and fragment and punycodes the host, so the result is all
+/// ASCII and this is what goes on the wire.
+fn encoded_length(url: &Url) -> usize {
+    url.as_str().len()
+}
+
+#[derive(Debug, PartialEq)]
+struct Truncated {
+    url: String,
+    dropped: Vec<String>,
+}
+
+fn truncate_query(url: &str, max: usize) -> Result<Truncated, String> {
+    let mut url = parse(url)?;
+    let mut params: Vec<String> = url
+        .query()
+        .map(|q| q.split('&').map(String::from).collect())
+        .unwrap_or_default();
+    let mut dropped = Vec::new();
+
+    while encoded_length(&url) > max {
+        let Some(param) = params.pop() else {
+            return Err(format!(
+                "URL is {} characters without a query, over the budget of {max}",
+                encoded_length(&url)
+            ));
+        };
+        if !param.is_empty() {
+            dropped.push(param);
+        }
+        // Params are already encoded, so rejoining them can't change their length.
+        let query = params.join("&");
+        url.set_query((!query.is_empty()).then_some(query.as_str()));
+    }
+
+    dropped.reverse();
+    Ok(Truncated {
+        url: url.into(),
+        dropped,
+    })
+}
+
+fn fragment_room(url: &str, max: usize) -> Result<usize, String> {
+    let mut url = parse(url)?;
+    url.set_fragment(None);
+    Ok(max.saturating_sub(encoded_length(&url) + 1))
+}
+
+/// Length of `url` after normalization and percent-encoding: what is sent
+/// over the wire, not the decoded string. `é` counts 6, `😀` counts 12.
+#[wasm_bindgen]
+pub fn encoded_url_length(url: &str) -> Result<usize, JsValue> {
+    parse(url)
+        .map(|url| encoded_length(&url))
+        .map_err(|e| JsValue::from_str(&e))
+}
+
+/// Whether the encoded form of `url` is at most `max` characters. Invalid
+/// URLs never fit.
+#[wasm_bindgen]
+pub fn fits_url_budget(url: &str, max: usize) -> bool {
+    parse(url).is_ok_and(|url| encoded_length(&url) <= max)
+}
+
+/// Drops whole query parameters from the end until the encoded URL fits in
+/// `max` characters. Surviving parameters keep their order and encoding, and
+/// the fragment is kept. Fails when the URL is too long even without a query.
+#[wasm_bindgen]
+pub fn truncate_query_to_budget(url: &str, max: usize) -> Result<String, JsValue> {
+    truncate_query(url, max)
+        .map(|truncated| truncated.url)
+        .map_err(|e| JsValue::from_str(&e))
+}
+
+/// Like `truncate_query_to_budget`, but returns `{url, dropped, length}` where
+/// `dropped` lists the removed `key=value` parameters in their original order.
+#[wasm_bindgen]
+pub fn truncate_query_to_budget_detailed(url: &str, max: usize) -> Result<JsValue, JsValue> {
+    let truncated = truncate_query(url, max).map_err(|e| JsValue::from_str(&e))?;
+    let dropped: Array = truncated.dropped.iter().map(JsValue::from).collect();
+
+    let obj = Object::new();
+    Reflect::set(&obj, &"length".into(), &(truncated.url.len() as u32).into())?;
+    Reflect::set(&obj, &"url".into(), &truncated.url.into())?;
+    Reflect::set(&obj, &"dropped".into(), &dropped)?;
+    Ok(obj.into())
+}
+
+/// How many encoded characters a fragment can use before `url` exceeds `max`.
+/// Any existing fragment is disregarded, since it would be replaced, and the
+/// `#` itself is accounted for. Returns 0 when there is no room.
+#[wasm_bindgen]
+pub fn fragment_budget_remaining(url: &str, max: usize) -> Result<usize, JsValue> {
+    fragment_room(url, max).map_err(|e| JsValue::from_str(&e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn length(url: &str) -> usize {
+        encoded_length(&parse(url).unwrap())
+    }
+
+    #[test]
+    fn counts_encoded_length() {
+        assert_eq!(length("https://a.com/"), 14);
+        assert_eq!(length("HTTPS://A.COM"), 14);
+        assert_eq!(length("https://a.com/é"), 20);
+        assert_eq!(length("https://a.com/😀"), 26);
+        assert_eq!(length("https://a.com/?q=a b"), 22);
+        assert_eq!(
+            length("https://bücher.de/"),
+            "https://xn--bcher-kva.de/".len()
+        );
+        assert!(fits_url_budget("https://a.com/😀", 26));
+        assert!(!fits_url_budget("https://a.com/😀", 25));
+        assert!(!fits_url_budget("not a url", 1000));
+    }
+
+    #[test]
+    fn drops_trailing_params_until_it_fits() {
+        let url = "https://a.com/p?a=1&b=%F0%9F%98%80&c=3#frag";
+        let truncated = truncate_query(url, 38).unwrap();
+        assert_eq!(truncated.url, "https://a.com/p?a=1#frag");
+        assert_eq!(truncated.dropped, ["b=%F0%9F%98%80", "c=3"]);
+
+        let truncated = truncate_query(url, 100).unwrap();
+        assert_eq!(truncated.url, url);
+        assert!(truncated.dropped.is_empty());
+    }
+
+    #[test]
+    fn never_cuts_a_value() {
+        let url = "https://a.com/?keep=1&long=aaaaaaaaaaaaaaaaaaaa&x=2";
+        for max in 21..=length(url) {
+            let truncated = truncate_query(url, max).unwrap();
+            assert!(truncated.url.len() <= max);
+            let query = Url::parse(&truncated.url)
+                .unwrap()
+                .query()
+                .map(String::from);
+            assert!(matches!(
+                query.as_deref(),
+                None | Some("keep=1")
+                    | Some("keep=1&long=aaaaaaaaaaaaaaaaaaaa")
+                    | Some("keep=1&long=aaaaaaaaaaaaaaaaaaaa&x=2")
+            ));
+        }
+    }
+
+    #[test]
+    fn drops_the_question_mark_with_the_last_param() {
+        let truncated = truncate_query("https://a.com/?only=1", 14).unwrap();
+        assert_eq!(truncated.url, "https://a.com/");
+        assert!(truncate_query("https://a.com/long/path", 10).is_err());
+    }
+
+    #[test]
+    fn computes_fragment_room() {
+        assert_eq!(fragment_room("https://a.com/", 20).unwrap(), 5);
+        assert_eq!(fragment_room("https://a.com/#old-state", 20).unwrap(), 5);
+        assert_eq!(fragment_room("https://a.com/", 14).unwrap(), 0);
+        assert!(fragment_room("nope", 20).is_err());
+    }
+}
@@ -0,0 +1,247 @@
+use js_sys::Array;
+use url::{Url, form_urlencoded};
+use wasm_bindgen::prelude::*;
+
+use super::encode_segment;
+
+/// Builds request URLs under a base URL whose path is always kept as a
+/// prefix: `https://api.example.com/api/v2` plus `users` is
+/// `https://api.example.com/api/v2/users`, with or without a trailing slash
+/// on the base and with or without a leading slash on the segment.
+///
+/// Each `path` call adds exactly one segment. Reserved characters, including
+/// `/` and `%`, are percent-encoded, and `.` and `..` are rejected, so user
+/// input can't climb out of the base path. Methods consume the builder so
+/// calls chain; use `clone` to branch off a partially built URL. Invalid
+/// input is reported by `build`.
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct ApiUrlBuilder {
+    base: Url,
+    segments: Vec<String>,
+    query: Vec<(String, String)>,
+    fragment: Option<String>,
+    error: Option<String>,
+}
+
+impl ApiUrlBuilder {
+    fn with_base(base: &str) -> Result<Self, String> {
+        let base = Url::parse(base.trim()).map_err(|e| format!("invalid base URL: {e}"))?;
+        if !matches!(base.scheme(), "http" | "https") {
+            return Err("base URL must be http or https".to_string());
+        }
+        if base.fragment().is_some() {
+            return Err("base URL must not have a fragment".to_string());
+        }
+
+        Ok(Self {
+            base,
+            segments: Vec::new(),
+            query: Vec::new(),
+            fragment: None,
+            error: None,
+        })
+    }
+
+    fn fail(mut self, error: String) -> Self {
+        self.error.get_or_insert(error);
+        self
+    }
+
+    fn finish(&self) -> Result<String, String> {
+        if let Some(error) = &self.error {
+            return Err(error.clone());
+        }
+
+        let mut url = self.base.clone();
+        if !self.segments.is_empty() {
+            let mut path = url.path().trim_end_matches('/').to_string();
+            for segment in &self.segments {
+                path.push('/');
+                path.push_str(segment);
+            }
+            url.set_path(&path);
+        }
+
+        if !self.query.is_empty() {
+            let mut query = form_urlencoded::Serializer::new(url.query().unwrap_or("").to_string());
+            query.extend_pairs(&self.query);
+            url.set_query(Some(&query.finish()));
+        }
+        url.set_fragment(self.fragment.as_deref());
+
+        Ok(url.into())
+    }
+}
+
+#[wasm_bindgen]
+impl ApiUrlBuilder {
+    /// Validates `base`, which must be an http(s) URL without a fragment. Its
+    /// path and query are kept.
+    #[wasm_bindgen(constructor)]
+    pub fn new(base: &str) -> Result<ApiUrlBuilder, JsValue> {
+        Self::with_base(base).map_err(|e| JsValue::from_str(&e))
+    }
+
+    /// Appends one path segment. Leading and trailing slashes are dropped and
+    /// anything else outside `A-Z a-z 0-9 - . _ ~` is percent-encoded. Empty
+    /// segments are ignored.
+    pub fn path(mut self, segment: &str) -> ApiUrlBuilder {
+        let segment = segment.trim_matches('/');
+        match segment {
+            "" => self,
+            "." | ".." => self.fail(format!("path segment '{segment}' is not allowed")),
+            _ => {
+                self.segments.push(encode_segment(segment));
+                self
+            }
+        }
+    }
+
+    pub fn query(mut self, key: &str, value: &str) -> ApiUrlBuilder {
+        self.query.push((key.to_string(), value.to_string()));
+        self
+    }
+
+    /// Adds `key=value` only when `value` is given.
+    pub fn query_opt(self, key: &str, value: Option<String>) -> ApiUrlBuilder {
+        match value {
+            Some(value) => self.query(key, &value),
+            None => self,
+        }
+    }
+
+    /// Adds `key=value` for each of `values`, in order. Strings, numbers and
+    /// booleans are accepted.
+    pub fn query_array(mut self, key: &str, values: Array) -> ApiUrlBuilder {
+        for (index, value) in values.iter().enumerate() {
+            let value = value
+                .as_string()
+                .or_else(|| value.as_f64().map(|n| n.to_string()))
+                .or_else(|| value.as_bool().map(|b| b.to_string()));
+            match value {
+                Some(value) => self.query.push((key.to_string(), value)),
+                None => {
+                    return self.fail(format!(
+                        "query_array '{key}': value {index} must be a string, number or boolean"
+                    ));
+                }
+            }
+        }
+        self
+    }
+
+    pub fn fragment(mut self, fragment: &str) -> ApiUrlBuilder {
+        self.fragment = Some(fragment.to_string());
+        self
+    }
+
+    /// A copy of this builder, so a partially built URL can be reused.
+    #[wasm_bindgen(js_name = clone)]
+    pub fn clone_builder(&self) -> ApiUrlBuilder {
+        self.clone()
+    }
+
+    /// Returns the URL, or the first error recorded while building it.
+    pub fn build(&self) -> Result<String, JsValue> {
+        self.finish().map_err(|e| JsValue::from_str(&e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn builder(base: &str) -> ApiUrlBuilder {
+        ApiUrlBuilder::with_base(base).unwrap()
+    }
+
+    #[test]
+    fn keeps_base_path_as_prefix() {
+        for base in [
+            "https://api.example.com/api/v2",
+            "https://api.example.com/api/v2/",
+        ] {
+            for segment in ["users", "/users", "users/", "//users//"] {
+                let url = builder(base).path(segment).path("42").finish().unwrap();
+                assert_eq!(url, "https://api.example.com/api/v2/users/42");
+            }
+        }
+        assert_eq!(
+            builder("https://api.example.com")
+                .path("users")
+                .finish()
+                .unwrap(),
+            "https://api.example.com/users"
+        );
+        assert_eq!(
+            builder("https://api.example.com/v1/").finish().unwrap(),
+            "https://api.example.com/v1/"
+        );
+    }
+
+    #[test]
+    fn encodes_reserved_characters_in_segments() {
+        let url = builder("https://a.com/api")
+            .path("a b/c?d#e%f")
+            .path("ünï")
+            .finish()
+            .unwrap();
+        assert_eq!(url, "https://a.com/api/a%20b%2Fc%3Fd%23e%25f/%C3%BCn%C3%AF");
+    }
+
+    #[test]
+    fn user_input_cannot_escape_the_base_path() {
+        for input in ["..", "../", "/../", ".", "./"] {
+            let result = builder("https://a.com/api/v2")
+                .path(input)
+                .path("admin")
+                .finish();
+            assert!(result.is_err(), "{input}");
+        }
+
+        for input in [
+            "../admin",
+            "..%2Fadmin",
+            "%2e%2e",
+            "..\\admin",
+            "a/../../admin",
+        ] {
+            let url = builder("https://a.com/api/v2")
+                .path(input)
+                .finish()
+                .unwrap();
+            assert!(url.starts_with("https://a.com/api/v2/"), "{input}: {url}");
+            let parsed = Url::parse(&url).unwrap();
+            assert_eq!(parsed.path_segments().unwrap().count(), 3, "{input}: {url}");
+        }
+    }
+
+    #[test]
+    fn builds_query_and_fragment() {
+        let url = builder("https://a.com/search?v=1")
+            .query("q", "a&b c")
+            .query_opt("page", Some("2".to_string()))
+            .query_opt("sort", None)
+            .fragment("top")
+            .finish()
+            .unwrap();
+        assert_eq!(url, "https://a.com/search?v=1&q=a%26b+c&page=2#top");
+    }
+
+    #[test]
+    fn clones_partially_built_urls() {
+        let users = builder("https://a.com/api").path("users");
+        let one = users.clone_builder().path("1").finish().unwrap();
+        let two = users.path("2").query("full", "true").finish().unwrap();
+        assert_eq!(one, "https://a.com/api/users/1");
+        assert_eq!(two, "https://a.com/api/users/2?full=true");
+    }
+
+    #[test]
+    fn rejects_bad_bases() {
+        assert!(ApiUrlBuilder::with_base("/relative").is_err());
+        assert!(ApiUrlBuilder::with_base("ftp://a.com/").is_err());
+        assert!(ApiUrlBuilder::with_base("https://a.com/#frag").is_err());
+    }
+}
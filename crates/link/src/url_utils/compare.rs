@@ -0,0 +1,374 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use js_sys::{Array, Object, Reflect};
+use url::{Position, Url};
+use wasm_bindgen::prelude::*;
+
+use super::{Ignore, canonical, read_strings};
+
+#[derive(Clone, Copy, Debug, Default)]
+struct Options {
+    ignore_query: bool,
+    ignore_fragment: bool,
+    ignore_trailing_slash: bool,
+    /// Compare from the path on, e.g. staging against production hosts.
+    ignore_origin: bool,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Difference {
+    Query,
+    Fragment,
+    TrailingSlash,
+}
+
+#[derive(Debug, Default)]
+struct Report {
+    only_in_a: Vec<String>,
+    only_in_b: Vec<String>,
+    in_both: Vec<String>,
+    query: Vec<(String, String)>,
+    fragment: Vec<(String, String)>,
+    trailing_slash: Vec<(String, String)>,
+    invalid_a: Vec<String>,
+    invalid_b: Vec<String>,
+    duplicates_a: usize,
+    duplicates_b: usize,
+}
+
+struct Entry<'a> {
+    original: &'a str,
+    url: Url,
+    key: String,
+}
+
+impl Options {
+    fn key(&self, url: &Url, also: Option<Difference>) -> String {
+        let ignore = Ignore {
+            fragment: self.ignore_fragment || also == Some(Difference::Fragment),
+            query: self.ignore_query || also == Some(Difference::Query),
+            trailing_slash: self.ignore_trailing_slash || also == Some(Difference::TrailingSlash),
+        };
+        let url = canonical(url, ignore);
+        if self.ignore_origin {
+            url[Position::BeforePath..].to_string()
+        } else {
+            url.to_string()
+        }
+    }
+
+    /// Parses and dedupes one list, returning the entries plus the invalid
+    /// inputs and the number of duplicates dropped.
+    fn prepare<'a>(&self, list: &'a [Option<String>]) -> (Vec<Entry<'a>>, Vec<String>, usize) {
+        let mut entries = Vec::new();
+        let mut invalid = Vec::new();
+        let mut seen = HashSet::new();
+        let mut duplicates = 0;
+
+        for (index, href) in list.iter().enumerate() {
+            let Some(href) = href else {
+                invalid.push(format!("<non-string entry {index}>"));
+                continue;
+            };
+            let Ok(url) = Url::parse(href.trim()) else {
+                invalid.push(href.clone());
+                continue;
+            };
+            let key = self.key(&url, None);
+            if seen.insert(key.clone()) {
+                entries.push(Entry {
+                    original: href,
+                    url,
+                    key,
+                });
+            } else {
+                duplicates += 1;
+            }
+        }
+
+        (entries, invalid, duplicates)
+    }
+}
+
+fn compare(a: &[Option<String>], b: &[Option<String>], options: Options) -> Report {
+    let (a, invalid_a, duplicates_a) = options.prepare(a);
+    let (b, invalid_b, duplicates_b) = options.prepare(b);
+    let mut report = Report {
+        invalid_a,
+        invalid_b,
+        duplicates_a,
+        duplicates_b,
+        ..Report::default()
+    };
+
+    let b_index: HashMap<&str, usize> = b
+        .iter()
+        .enumerate()
+        .map(|(i, entry)| (entry.key.as_str(), i))
+        .collect();
+    let mut a_matched = vec![false; a.len()];
+    let mut b_matched = vec![false; b.len()];
+
+    for (i, entry) in a.iter().enumerate() {
+        if let Some(&j) = b_index.get(entry.key.as_str()) {
+            a_matched[i] = true;
+            b_matched[j] = true;
+            report.in_both.push(entry.original.to_string());
+        }
+    }
+
+    // Pair the leftovers that become equal once exactly one more kind of
+    // difference is ignored. Kinds the options already ignore can't differ.
+    let kinds = [
+        (Difference::Fragment, options.ignore_fragment),
+        (Difference::TrailingSlash, options.ignore_trailing_slash),
+        (Difference::Query, options.ignore_query),
+    ];
+    for (kind, _) in kinds.into_iter().filter(|(_, ignored)| !ignored) {
+        let mut candidates: HashMap<String, VecDeque<usize>> = HashMap::new();
+        for (j, entry) in b.iter().enumerate().filter(|(j, _)| !b_matched[*j]) {
+            candidates
+                .entry(options.key(&entry.url, Some(kind)))
+                .or_default()
+                .push_back(j);
+        }
+
+        for (i, entry) in a.iter().enumerate() {
+            if a_matched[i] {
+                continue;
+            }
+            let key = options.key(&entry.url, Some(kind));
+            let Some(j) = candidates.get_mut(&key).and_then(VecDeque::pop_front) else {
+                continue;
+            };
+            a_matched[i] = true;
+            b_matched[j] = true;
+
+            let pair = (entry.original.to_string(), b[j].original.to_string());
+            match kind {
+                Difference::Query => report.query.push(pair),
+                Difference::Fragment => report.fragment.push(pair),
+                Difference::TrailingSlash => report.trailing_slash.push(pair),
+            }
+        }
+    }
+
+    report.only_in_a = unmatched(&a, &a_matched);
+    report.only_in_b = unmatched(&b, &b_matched);
+    report
+}
+
+fn unmatched(entries: &[Entry], matched: &[bool]) -> Vec<String> {
+    entries
+        .iter()
+        .zip(matched)
+        .filter(|(_, matched)| !**matched)
+        .map(|(entry, _)| entry.original.to_string())
+        .collect()
+}
+
+fn read_options(value: &JsValue) -> Options {
+    let flag = |name: &str| {
+        Reflect::get(value, &name.into())
+            .ok()
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false)
+    };
+    Options {
+        ignore_query: flag("ignore_query"),
+        ignore_fragment: flag("ignore_fragment"),
+        ignore_trailing_slash: flag("ignore_trailing_slash"),
+        ignore_origin: flag("ignore_origin"),
+    }
+}
+
+fn strings(values: &[String]) -> Array {
+    values.iter().map(JsValue::from).collect()
+}
+
+fn pairs(values: &[(String, String)]) -> Array {
+    values
+        .iter()
+        .map(|(a, b)| {
+            let obj = Object::new();
+            let _ = Reflect::set(&obj, &"a".into(), &a.into());
+            let _ = Reflect::set(&obj, &"b".into(), &b.into());
+            JsValue::from(obj)
+        })
+        .collect()
+}
+
+/// Compares two URL lists, e.g. staging against production, and returns
+/// `{only_in_a, only_in_b, in_both, differs_only_by: {query, fragment,
+/// trailing_slash}, invalid: {a, b}, duplicates: {a, b}}`.
+///
+/// URLs are compared canonically (case-insensitive scheme and host, default
+/// ports dropped). Each list is deduplicated first and the number of dropped
+/// duplicates reported. Unmatched URLs that would match if only their query,
+/// fragment or trailing slash were ignored are reported as `{a, b}` pairs of
+/// the original strings. `options` may set `ignore_query`,
+/// `ignore_fragment` and `ignore_trailing_slash` to treat those differences
+/// as equal instead, and `ignore_origin` to compare paths across hosts.
+/// Entries that aren't absolute URLs are listed under `invalid`.
+#[wasm_bindgen]
+pub fn compare_url_sets(a: Array, b: Array, options: JsValue) -> JsValue {
+    let report = compare(&read_strings(&a), &read_strings(&b), read_options(&options));
+
+    let differs = Object::new();
+    let _ = Reflect::set(&differs, &"query".into(), &pairs(&report.query));
+    let _ = Reflect::set(&differs, &"fragment".into(), &pairs(&report.fragment));
+    let _ = Reflect::set(
+        &differs,
+        &"trailing_slash".into(),
+        &pairs(&report.trailing_slash),
+    );
+
+    let invalid = Object::new();
+    let _ = Reflect::set(&invalid, &"a".into(), &strings(&report.invalid_a));
+    let _ = Reflect::set(&invalid, &"b".into(), &strings(&report.invalid_b));
+
+    let duplicates = Object::new();
+    let _ = Reflect::set(
+        &duplicates,
+        &"a".into(),
+        &(report.duplicates_a as u32).into(),
+    );
+    let _ = Reflect::set(
+        &duplicates,
+        &"b".into(),
+        &(report.duplicates_b as u32).into(),
+    );
+
+    let obj = Object::new();
+    let _ = Reflect::set(&obj, &"only_in_a".into(), &strings(&report.only_in_a));
+    let _ = Reflect::set(&obj, &"only_in_b".into(), &strings(&report.only_in_b));
+    let _ = Reflect::set(&obj, &"in_both".into(), &strings(&report.in_both));
+    let _ = Reflect::set(&obj, &"differs_only_by".into(), &differs);
+    let _ = Reflect::set(&obj, &"invalid".into(), &invalid);
+    let _ = Reflect::set(&obj, &"duplicates".into(), &duplicates);
+    obj.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn list(urls: &[&str]) -> Vec<Option<String>> {
+        urls.iter().map(|url| Some(url.to_string())).collect()
+    }
+
+    fn pair(a: &str, b: &str) -> (String, String) {
+        (a.to_string(), b.to_string())
+    }
+
+    #[test]
+    fn splits_into_only_and_both() {
+        let report = compare(
+            &list(&[
+                "https://a.com/x",
+                "https://a.com/only-a",
+                "HTTPS://A.COM:443/y",
+            ]),
+            &list(&["https://a.com/y", "https://a.com/x", "https://a.com/only-b"]),
+            Options::default(),
+        );
+        assert_eq!(report.in_both, ["https://a.com/x", "HTTPS://A.COM:443/y"]);
+        assert_eq!(report.only_in_a, ["https://a.com/only-a"]);
+        assert_eq!(report.only_in_b, ["https://a.com/only-b"]);
+    }
+
+    #[test]
+    fn reports_single_kind_differences_as_pairs() {
+        let report = compare(
+            &list(&[
+                "https://a.com/q?v=1",
+                "https://a.com/f#top",
+                "https://a.com/s/",
+                "https://a.com/both?v=1#top",
+            ]),
+            &list(&[
+                "https://a.com/q?v=2",
+                "https://a.com/f",
+                "https://a.com/s",
+                "https://a.com/both",
+            ]),
+            Options::default(),
+        );
+        assert_eq!(
+            report.query,
+            [pair("https://a.com/q?v=1", "https://a.com/q?v=2")]
+        );
+        assert_eq!(
+            report.fragment,
+            [pair("https://a.com/f#top", "https://a.com/f")]
+        );
+        assert_eq!(
+            report.trailing_slash,
+            [pair("https://a.com/s/", "https://a.com/s")]
+        );
+        assert_eq!(report.only_in_a, ["https://a.com/both?v=1#top"]);
+        assert_eq!(report.only_in_b, ["https://a.com/both"]);
+    }
+
+    #[test]
+    fn ignored_differences_count_as_equal() {
+        let options = Options {
+            ignore_query: true,
+            ignore_fragment: true,
+            ignore_trailing_slash: true,
+            ..Options::default()
+        };
+        let report = compare(
+            &list(&["https://a.com/both/?v=1#top"]),
+            &list(&["https://a.com/both"]),
+            options,
+        );
+        assert_eq!(report.in_both, ["https://a.com/both/?v=1#top"]);
+        assert!(report.query.is_empty() && report.only_in_b.is_empty());
+    }
+
+    #[test]
+    fn ignore_origin_compares_paths() {
+        let options = Options {
+            ignore_origin: true,
+            ..Options::default()
+        };
+        let report = compare(
+            &list(&["https://staging.a.com/docs?x=1"]),
+            &list(&["https://a.com/docs?x=1"]),
+            options,
+        );
+        assert_eq!(report.in_both, ["https://staging.a.com/docs?x=1"]);
+    }
+
+    #[test]
+    fn collects_invalid_and_counts_duplicates() {
+        let mut a = list(&[
+            "https://a.com/",
+            "https://A.com",
+            "/relative",
+            "https://a.com/",
+        ]);
+        a.push(None);
+        let report = compare(&a, &list(&["nope", "https://a.com"]), Options::default());
+        assert_eq!(report.invalid_a, ["/relative", "<non-string entry 4>"]);
+        assert_eq!(report.invalid_b, ["nope"]);
+        assert_eq!(report.duplicates_a, 2);
+        assert_eq!(report.duplicates_b, 0);
+        assert_eq!(report.in_both, ["https://a.com/"]);
+    }
+
+    #[test]
+    fn handles_ten_thousand_urls() {
+        let a: Vec<_> = (0..10_000)
+            .map(|i| Some(format!("https://a.com/p/{i}")))
+            .collect();
+        let b: Vec<_> = (5_000..15_000)
+            .map(|i| Some(format!("https://a.com/p/{i}/")))
+            .collect();
+        let report = compare(&a, &b, Options::default());
+        assert_eq!(report.trailing_slash.len(), 5_000);
+        assert_eq!(report.only_in_a.len(), 5_000);
+        assert_eq!(report.only_in_b.len(), 5_000);
+    }
+}
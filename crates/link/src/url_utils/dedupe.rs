@@ -0,0 +1,222 @@
+use std::collections::{HashMap, HashSet};
+
+use js_sys::{Array, Object, Reflect};
+use url::Url;
+use wasm_bindgen::prelude::*;
+
+use super::{Ignore, canonical_url, read_strings, registrable_domain};
+
+/// Bucket for entries that are not absolute URLs. `.invalid` is a reserved
+/// TLD, so it can't collide with a real registrable domain.
+const INVALID: &str = "invalid";
+
+fn dedupe(urls: &[Option<String>], ignore_fragment: bool, ignore_query: bool) -> Vec<&str> {
+    let ignore = Ignore {
+        fragment: ignore_fragment,
+        query: ignore_query,
+        trailing_slash: true,
+    };
+    let mut seen = HashSet::new();
+
+    urls.iter()
+        .flatten()
+        .filter(|href| {
+            let key = match Url::parse(href.trim()) {
+                Ok(url) => canonical_url(&url, ignore),
+                Err(_) => href.trim().to_string(),
+            };
+            seen.insert(key)
+        })
+        .map(String::as_str)
+        .collect()
+}
+
+/// Groups in first-seen order: a list of buckets plus an index into it.
+#[derive(Default)]
+struct Buckets<T> {
+    order: Vec<(String, T)>,
+    index: HashMap<String, usize>,
+}
+
+impl<T: Default> Buckets<T> {
+    fn entry(&mut self, key: String) -> &mut T {
+        let index = *self.index.entry(key.clone()).or_insert_with(|| {
+            self.order.push((key, T::default()));
+            self.order.len() - 1
+        });
+        &mut self.order[index].1
+    }
+}
+
+fn group_by_domain(urls: &[Option<String>]) -> Vec<(String, Vec<String>)> {
+    let mut buckets = Buckets::<Vec<String>>::default();
+
+    for (index, href) in urls.iter().enumerate() {
+        let Some(href) = href else {
+            buckets
+                .entry(INVALID.to_string())
+                .push(format!("<non-string entry {index}>"));
+            continue;
+        };
+        let key = match Url::parse(href.trim()) {
+            Ok(url) => match url.host() {
+                Some(host) => registrable_domain(&host),
+                // mailto:, data: and friends have no host; group them by scheme.
+                None => format!("{}:", url.scheme()),
+            },
+            Err(_) => INVALID.to_string(),
+        };
+        buckets.entry(key).push(href.clone());
+    }
+
+    buckets.order
+}
+
+fn count_by_scheme(urls: &[Option<String>]) -> Vec<(String, u32)> {
+    let mut buckets = Buckets::<u32>::default();
+
+    for href in urls {
+        let key = href
+            .as_deref()
+            .and_then(|href| Url::parse(href.trim()).ok())
+            .map_or_else(|| INVALID.to_string(), |url| url.scheme().to_string());
+        *buckets.entry(key) += 1;
+    }
+
+    buckets.order
+}
+
+/// Removes duplicate URLs, keeping the first occurrence of each. URLs are
+/// compared canonically (case-insensitive scheme and host, default ports and
+/// trailing slashes ignored), optionally also ignoring the fragment and/or
+/// query. Entries that are not absolute URLs are deduplicated by their trimmed
+/// text; non-string entries are skipped.
+#[wasm_bindgen]
+pub fn dedupe_urls(urls: Array, ignore_fragment: bool, ignore_query: bool) -> Array {
+    let urls = read_strings(&urls);
+    dedupe(&urls, ignore_fragment, ignore_query)
+        .into_iter()
+        .map(JsValue::from)
+        .collect()
+}
+
+/// Groups URLs by registrable domain (`news.bbc.co.uk` → `bbc.co.uk`) into an
+/// object of arrays, in first-seen order. Hostless URLs such as `mailto:` are
+/// grouped by scheme (`"mailto:"`); relative, malformed and non-string entries
+/// go to the `"invalid"` bucket.
+#[wasm_bindgen]
+pub fn group_urls_by_domain(urls: Array) -> JsValue {
+    let obj = Object::new();
+    for (key, group) in group_by_domain(&read_strings(&urls)) {
+        let group: Array = group.iter().map(JsValue::from).collect();
+        let _ = Reflect::set(&obj, &key.into(), &group);
+    }
+    obj.into()
+}
+
+/// Counts URLs per scheme (`{https: 12, mailto: 1, invalid: 2}`). Relative,
+/// malformed and non-string entries are counted as `"invalid"`.
+#[wasm_bindgen]
+pub fn count_urls_by_scheme(urls: Array) -> JsValue {
+    let obj = Object::new();
+    for (scheme, count) in count_by_scheme(&read_strings(&urls)) {
+        let _ = Reflect::set(&obj, &scheme.into(), &count.into());
+    }
+    obj.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn owned(urls: &[&str]) -> Vec<Option<String>> {
+        urls.iter().map(|url| Some(url.to_string())).collect()
+    }
+
+    #[test]
+    fn dedupes_canonically_in_first_seen_order() {
+        let urls = owned(&[
+            "https://Example.com/a/",
+            "https://example.com:443/a",
+            "https://example.com/a#x",
+            "https://example.com/a?q=1",
+            "/relative",
+            "/relative",
+        ]);
+        assert_eq!(
+            dedupe(&urls, false, false),
+            [
+                "https://Example.com/a/",
+                "https://example.com/a#x",
+                "https://example.com/a?q=1",
+                "/relative"
+            ]
+        );
+        assert_eq!(
+            dedupe(&urls, true, true),
+            ["https://Example.com/a/", "/relative"]
+        );
+    }
+
+    #[test]
+    fn skips_non_string_entries_when_deduping() {
+        let urls = vec![None, Some("https://a.com/".to_string())];
+        assert_eq!(dedupe(&urls, false, false), ["https://a.com/"]);
+    }
+
+    #[test]
+    fn groups_by_registrable_domain_with_invalid_bucket() {
+        let mut urls = owned(&[
+            "https://www.bbc.co.uk/news",
+            "mailto:someone@example.com",
+            "not a url",
+            "https://bbc.co.uk/",
+            "https://cdn.example.com/x.js",
+        ]);
+        urls.push(None);
+
+        let groups = group_by_domain(&urls);
+        let keys: Vec<&str> = groups.iter().map(|(key, _)| key.as_str()).collect();
+        assert_eq!(keys, ["bbc.co.uk", "mailto:", "invalid", "example.com"]);
+        assert_eq!(
+            groups[0].1,
+            ["https://www.bbc.co.uk/news", "https://bbc.co.uk/"]
+        );
+        assert_eq!(groups[2].1, ["not a url", "<non-string entry 5>"]);
+    }
+
+    #[test]
+    fn counts_by_scheme() {
+        let mut urls = owned(&[
+            "https://a.com",
+            "HTTP://b.com",
+            "https://c.com",
+            "tel:+1555",
+            "//cdn.com/x",
+        ]);
+        urls.push(None);
+
+        let counts = count_by_scheme(&urls);
+        let counts: Vec<(&str, u32)> = counts.iter().map(|(s, n)| (s.as_str(), *n)).collect();
+        assert_eq!(
+            counts,
+            [("https", 2), ("http", 1), ("tel", 1), ("invalid", 2)]
+        );
+    }
+
+    #[test]
+    fn handles_ten_thousand_urls() {
+        let urls: Vec<Option<String>> = (0..10_000)
+            .map(|i| {
+                Some(format!(
+                    "https://site{}.example.com/page/{}",
+                    i % 50,
+                    i % 2_000
+                ))
+            })
+            .collect();
+        assert_eq!(dedupe(&urls, true, true).len(), 2_000);
+        assert_eq!(group_by_domain(&urls).len(), 1);
+        assert_eq!(count_by_scheme(&urls), [("https".to_string(), 10_000)]);
+    }
+}
@@ -0,0 +1,445 @@
+use url::{ParseError, Url, quirks};
+use wasm_bindgen::prelude::*;
+
+use super::registrable_domain;
+
+const ELLIPSIS: &str = "…";
+
+/// Whether `c` attaches to the preceding character instead of starting a new
+/// grapheme cluster. An approximation of UAX #29 covering combining marks,
+/// joiners, variation selectors, emoji modifiers and tag sequences, which is
+/// what shows up in URLs without pulling in the full segmentation tables.
+fn is_extending(c: char) -> bool {
+    matches!(
+        c as u32,
+        0x0300..=0x036F
+            | 0x0483..=0x0489
+            | 0x0591..=0x05BD
+            | 0x0610..=0x061A
+            | 0x064B..=0x065F
+            | 0x0900..=0x0903
+            | 0x093A..=0x094F
+            | 0x0E31
+            | 0x0E34..=0x0E3A
+            | 0x0E47..=0x0E4E
+            | 0x1AB0..=0x1AFF
+            | 0x1DC0..=0x1DFF
+            | 0x200C..=0x200D
+            | 0x20D0..=0x20FF
+            | 0x3099..=0x309A
+            | 0xFE00..=0xFE0F
+            | 0xFE20..=0xFE2F
+            | 0x1F3FB..=0x1F3FF
+            | 0xE0020..=0xE007F
+            | 0xE0100..=0xE01EF
+    )
+}
+
+fn is_regional_indicator(c: char) -> bool {
+    matches!(c as u32, 0x1F1E6..=0x1F1FF)
+}
+
+/// Splits `s` into approximate grapheme clusters; see `is_extending`.
+fn graphemes(s: &str) -> Vec<&str> {
+    let mut clusters = Vec::new();
+    let mut start = 0;
+    let mut prev: Option<char> = None;
+    let mut regional_run = 0;
+
+    for (i, c) in s.char_indices() {
+        let joins = match prev {
+            None => false,
+            Some('\r') => c == '\n',
+            Some('\u{200D}') => true,
+            Some(p) if is_regional_indicator(p) && is_regional_indicator(c) => {
+                regional_run % 2 == 1
+            }
+            Some(_) => is_extending(c),
+        };
+        if !joins && i > 0 {
+            clusters.push(&s[start..i]);
+            start = i;
+        }
+        regional_run = if is_regional_indicator(c) {
+            regional_run + 1
+        } else {
+            0
+        };
+        prev = Some(c);
+    }
+    if start < s.len() {
+        clusters.push(&s[start..]);
+    }
+    clusters
+}
+
+fn width(s: &str) -> usize {
+    graphemes(s).len()
+}
+
+/// A `?` or `#` right before an ellipsis or at the end reads as a broken URL.
+fn trim_dangling(s: &str) -> &str {
+    s.trim_end_matches(['?', '#', '&', '='])
+}
+
+fn middle_ellipsis(s: &str, max: usize) -> String {
+    let clusters = graphemes(s);
+    if clusters.len() <= max {
+        return s.to_string();
+    }
+    if max == 0 {
+        return String::new();
+    }
+
+    let keep = max - 1;
+    let head = clusters[..keep.div_ceil(2)].concat();
+    let tail = clusters[clusters.len() - keep / 2..].concat();
+    format!("{}{ELLIPSIS}{}", trim_dangling(&head), tail)
+}
+
+/// Keeps the end of `host`, where the registrable domain is.
+fn left_truncate(host: &str, max: usize) -> String {
+    let clusters = graphemes(host);
+    if clusters.len() <= max {
+        return host.to_string();
+    }
+    if max == 0 {
+        return String::new();
+    }
+    let tail = clusters[clusters.len() - (max - 1)..].concat();
+    format!("{ELLIPSIS}{}", tail.trim_start_matches('.'))
+}
+
+/// Characters that stay percent-encoded when displayed: controls and
+/// whitespace, which would break the line or hide where the URL ends, bidi
+/// and invisible format characters, which can make `…%E2%80%AEfdp.exe` read
+/// as `…exe.pdf`, and `?`/`#`, which would look like a query or fragment.
+/// Zero-width (non-)joiners stay decoded since emoji and scripts need them.
+fn keeps_encoding(c: char) -> bool {
+    c.is_control()
+        || c.is_whitespace()
+        || matches!(
+            c,
+            '?' | '#'
+                | '\u{00AD}'
+                | '\u{061C}'
+                | '\u{200B}'
+                | '\u{200E}'
+                | '\u{200F}'
+                | '\u{202A}'..='\u{202E}'
+                | '\u{2060}'..='\u{2064}'
+                | '\u{2066}'..='\u{2069}'
+                | '\u{FEFF}'
+        )
+}
+
+/// Decodes one run of `%XX` sequences. Bytes that aren't UTF-8 and characters
+/// that `keeps_encoding` stay as written, since showing replacement
+/// characters or invisible controls would be worse.
+fn decode_run(encoded: &str, out: &mut String) {
+    let bytes: Vec<u8> = (0..encoded.len())
+        .step_by(3)
+        .filter_map(|i| u8::from_str_radix(&encoded[i + 1..i + 3], 16).ok())
+        .collect();
+    let mut at = 0;
+    for chunk in bytes.utf8_chunks() {
+        for c in chunk.valid().chars() {
+            let end = at + 3 * c.len_utf8();
+            if keeps_encoding(c) {
+                out.push_str(&encoded[at..end]);
+            } else {
+                out.push(c);
+            }
+            at = end;
+        }
+        let end = at + 3 * chunk.invalid().len();
+        out.push_str(&encoded[at..end]);
+        at = end;
+    }
+}
+
+/// Percent-decodes for display; see `decode_run` for what stays encoded.
+fn percent_decode(s: &str) -> String {
+    let is_escape = |i: usize| {
+        s.as_bytes()[i] == b'%'
+            && s.get(i + 1..i + 3)
+                .is_some_and(|hex| hex.bytes().all(|b| b.is_ascii_hexdigit()))
+    };
+    let mut out = String::with_capacity(s.len());
+    let mut i = 0;
+    while i < s.len() {
+        if !is_escape(i) {
+            let from = i + s[i..].chars().next().map_or(1, char::len_utf8);
+            let next = s[from..].find('%').map_or(s.len(), |n| from + n);
+            out.push_str(&s[i..next]);
+            i = next;
+            continue;
+        }
+        let mut end = i;
+        while end < s.len() && is_escape(end) {
+            end += 3;
+        }
+        decode_run(&s[i..end], &mut out);
+        i = end;
+    }
+    out
+}
+
+fn pretty_host(url: &Url) -> Option<String> {
+    let host = quirks::domain_to_unicode(url.host_str()?).to_lowercase();
+    let host = host.strip_prefix("www.").unwrap_or(&host).to_string();
+    Some(host)
+}
+
+fn display(url: &str, max: usize) -> String {
+    let Ok(parsed) = Url::parse(url.trim()) else {
+        return middle_ellipsis(url.trim(), max);
+    };
+    let Some(mut host) = pretty_host(&parsed) else {
+        // mailto:, data: and friends: nothing structured to keep.
+        return middle_ellipsis(parsed.as_str(), max);
+    };
+    if let Some(port) = parsed.port() {
+        host.push_str(&format!(":{port}"));
+    }
+
+    let path = match percent_decode(parsed.path()) {
+        path if path == "/" => String::new(),
+        path => path,
+    };
+    let query = parsed
+        .query()
+        .filter(|q| !q.is_empty())
+        .map(|q| format!("?{}", percent_decode(q)))
+        .unwrap_or_default();
+    let fragment = parsed
+        .fragment()
+        .filter(|f| !f.is_empty())
+        .map(|f| format!("#{}", percent_decode(f)))
+        .unwrap_or_default();
+
+    let candidates = [
+        format!("{host}{path}{query}{fragment}"),
+        format!("{host}{path}{query}"),
+        format!("{host}{path}"),
+    ];
+    if let Some(fit) = candidates.into_iter().find(|c| width(c) <= max) {
+        return fit;
+    }
+
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    let trailing = if path.ends_with('/') { "/" } else { "" };
+    if let Some((last, rest)) = segments.split_last() {
+        let last = format!("{last}{trailing}");
+        if let Some(first) = rest.first().filter(|_| rest.len() > 1) {
+            let elided = format!("{host}/{first}/{ELLIPSIS}/{last}");
+            if width(&elided) <= max {
+                return elided;
+            }
+        }
+
+        let prefix = if rest.is_empty() {
+            format!("{host}/")
+        } else {
+            format!("{host}/{ELLIPSIS}/")
+        };
+        let elided = format!("{prefix}{last}");
+        if width(&elided) <= max {
+            return elided;
+        }
+        // Shorten the final segment in the middle so an extension stays visible;
+        // below three characters it is no longer recognisable.
+        let room = max.saturating_sub(width(&prefix));
+        if room >= 3 {
+            return format!("{prefix}{}", middle_ellipsis(&last, room));
+        }
+    }
+
+    if width(&host) <= max {
+        return host;
+    }
+    let domain = parsed
+        .host()
+        .map(|h| quirks::domain_to_unicode(&registrable_domain(&h)))
+        .unwrap_or_default();
+    if max > width(&domain) {
+        left_truncate(&host, max)
+    } else {
+        middle_ellipsis(&domain, max)
+    }
+}
+
+/// Shortens `url` for display in at most `max_chars` grapheme clusters.
+///
+/// The scheme and `www.` are dropped, the host is shown in unicode form and
+/// the path, query and fragment are percent-decoded, except for controls,
+/// whitespace, bidi and invisible characters and `?`/`#`. When too long, the
+/// fragment and then the query are dropped, middle path segments collapse to
+/// `/…/`, and the final segment is shortened in the middle. Only when the
+/// host alone doesn't fit is it cut from the left, keeping the registrable
+/// domain visible. Strings that aren't URLs get a plain middle ellipsis.
+#[wasm_bindgen]
+pub fn display_url(url: &str, max_chars: usize) -> String {
+    display(url, max_chars)
+}
+
+/// The host of `url` in unicode form, lowercased and without `www.`. Bare
+/// hosts such as `xn--bcher-kva.de` are accepted; anything else yields `""`.
+#[wasm_bindgen]
+pub fn display_domain(url: &str) -> String {
+    let url = url.trim();
+    let parsed = match Url::parse(url) {
+        Err(ParseError::RelativeUrlWithoutBase) => Url::parse(&format!("http://{url}")),
+        parsed => parsed,
+    };
+    parsed
+        .ok()
+        .and_then(|parsed| pretty_host(&parsed))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn graphemes_keep_clusters_together() {
+        assert_eq!(graphemes("e\u{301}x"), ["e\u{301}", "x"]);
+        assert_eq!(graphemes("👍🏽!"), ["👍🏽", "!"]);
+        assert_eq!(
+            graphemes("👨\u{200D}👩\u{200D}👧"),
+            ["👨\u{200D}👩\u{200D}👧"]
+        );
+        assert_eq!(graphemes("🇩🇪🇫🇷"), ["🇩🇪", "🇫🇷"]);
+        assert_eq!(graphemes("a\r\nb"), ["a", "\r\n", "b"]);
+    }
+
+    #[test]
+    fn display_domain_is_unicode_without_www() {
+        assert_eq!(
+            display_domain("https://www.xn--bcher-kva.de/x"),
+            "bücher.de"
+        );
+        assert_eq!(
+            display_domain("HTTPS://WWW.Example.COM:8080/"),
+            "example.com"
+        );
+        assert_eq!(display_domain("xn--mnchen-3ya.de"), "münchen.de");
+        assert_eq!(display_domain("mailto:a@b.com"), "");
+    }
+
+    #[test]
+    fn never_exceeds_budget() {
+        let url = "https://www.example.com/a/very/deep/path/with/a-long-file-name.html?q=1#frag";
+        for max in 0..100 {
+            let shown = display(url, max);
+            assert!(width(&shown) <= max, "{max}: {shown}");
+            assert!(!shown.ends_with(['?', '#']), "{max}: {shown}");
+        }
+
+        // Encoded `?` and `#` are part of the path, not a dangling delimiter.
+        for url in [
+            "https://example.com/a%3F",
+            "https://example.com/dir/file%23?q=%3F#x%23",
+        ] {
+            for max in 0..50 {
+                let shown = display(url, max);
+                assert!(width(&shown) <= max, "{max}: {shown}");
+                assert!(!shown.ends_with(['?', '#']), "{max}: {shown}");
+            }
+        }
+    }
+
+    #[test]
+    fn keeps_invisible_and_control_characters_encoded() {
+        let shown = display("https://example.com/invoice%E2%80%AEfdp.exe", 60);
+        assert_eq!(shown, "example.com/invoice%E2%80%AEfdp.exe");
+        assert!(!shown.contains('\u{202E}'));
+
+        for (encoded, expected) in [
+            ("a%0Ab", "a%0Ab"),
+            ("a%0d%0Ab", "a%0d%0Ab"),
+            ("a%20b%09c", "a%20b%09c"),
+            (
+                "%E2%80%8Fx%E2%81%A6y%EF%BB%BF",
+                "%E2%80%8Fx%E2%81%A6y%EF%BB%BF",
+            ),
+            ("a%3Fb%23c", "a%3Fb%23c"),
+            ("caf%C3%A9%E2%80%AEx%FF", "café%E2%80%AEx%FF"),
+            ("%F0%9F%91%A8%E2%80%8D%F0%9F%91%A9", "👨\u{200D}👩"),
+            ("100%", "100%"),
+            ("%zz%4", "%zz%4"),
+            ("é%C3%A9", "éé"),
+        ] {
+            assert_eq!(percent_decode(encoded), expected, "{encoded}");
+        }
+        assert_eq!(display("https://example.com/a%3F", 40), "example.com/a%3F");
+    }
+
+    /// `(url, max_chars, expected)` for URLs seen in the wild.
+    const SNAPSHOTS: &[(&str, usize, &str)] = &[
+        ("https://www.example.com/", 40, "example.com"),
+        (
+            "https://en.wikipedia.org/wiki/Caf%C3%A9_au_lait",
+            60,
+            "en.wikipedia.org/wiki/Café_au_lait",
+        ),
+        (
+            "https://github.com/rust-lang/rust/blob/master/library/core/src/lib.rs",
+            40,
+            "github.com/rust-lang/…/lib.rs",
+        ),
+        (
+            "https://docs.example.com/guide/getting-started/installation?lang=en#linux",
+            40,
+            "docs.example.com/guide/…/installation",
+        ),
+        (
+            "https://www.amazon.com/Some-Product-Name-With-Many-Words/dp/B000000000/ref=sr_1_1?keywords=thing&qid=123",
+            30,
+            "amazon.com/…/ref=sr_1_1",
+        ),
+        (
+            "https://cdn.example.com/assets/images/2024/very-long-photo-name-from-camera.jpeg",
+            40,
+            "cdn.example.com/…/very-long-p…amera.jpeg",
+        ),
+        (
+            "https://www.xn--bcher-kva.de/k%C3%BCche/t%C3%B6pfe",
+            30,
+            "bücher.de/küche/töpfe",
+        ),
+        ("https://example.com/search?", 30, "example.com/search"),
+        ("https://example.com/page#", 30, "example.com/page"),
+        (
+            "http://localhost:8080/api/v1/users/42/settings",
+            25,
+            "localhost:8080/…/settings",
+        ),
+        (
+            "https://a.very.deeply.nested.subdomain.bbc.co.uk/news",
+            20,
+            "…subdomain.bbc.co.uk",
+        ),
+        ("https://news.bbc.co.uk/", 5, "bb…uk"),
+        ("not a url but a long piece of text", 12, "not a … text"),
+        (
+            "mailto:someone.with.a.long.name@example.com",
+            20,
+            "mailto:som…ample.com",
+        ),
+        ("https://example.com/%FF%FE/x", 30, "example.com/%FF%FE/x"),
+        (
+            "https://example.com/emoji/👍🏽👍🏽👍🏽👍🏽👍🏽",
+            24,
+            "example.com/emoji/👍🏽👍🏽👍🏽👍🏽👍🏽",
+        ),
+    ];
+
+    #[test]
+    fn snapshots() {
+        for (url, max, expected) in SNAPSHOTS {
+            assert_eq!(display(url, *max), *expected, "{url} @ {max}");
+        }
+    }
+}
@@ -0,0 +1,361 @@
+use js_sys::{Array, Number, Object, Reflect};
+use url::form_urlencoded;
+use wasm_bindgen::prelude::*;
+
+/// A form value after `null`/`undefined` have been dropped and scalars have
+/// been stringified the way `String(value)` does.
+#[derive(Clone, Debug, PartialEq)]
+enum FormValue {
+    Text(String),
+    List(Vec<FormValue>),
+    Map(Vec<(String, FormValue)>),
+}
+
+fn from_js(value: &JsValue, path: &str) -> Result<Option<FormValue>, String> {
+    if value.is_null() || value.is_undefined() {
+        return Ok(None);
+    }
+    if let Some(text) = value.as_string() {
+        return Ok(Some(FormValue::Text(text)));
+    }
+    if let Some(flag) = value.as_bool() {
+        return Ok(Some(FormValue::Text(flag.to_string())));
+    }
+    if let Some(number) = value.as_f64() {
+        let text = Number::from(number)
+            .to_string_with_radix(10)
+            .map_or_else(|_| number.to_string(), String::from);
+        return Ok(Some(FormValue::Text(text)));
+    }
+    if Array::is_array(value) {
+        let mut items = Vec::new();
+        for (index, item) in Array::from(value).iter().enumerate() {
+            items.extend(from_js(&item, &format!("{path}[{index}]"))?);
+        }
+        return Ok(Some(FormValue::List(items)));
+    }
+    if value.is_object() && !value.is_function() {
+        return read_map(value, path).map(|map| Some(FormValue::Map(map)));
+    }
+    Err(format!(
+        "'{path}' must be a string, number, boolean, array or object"
+    ))
+}
+
+fn read_map(value: &JsValue, path: &str) -> Result<Vec<(String, FormValue)>, String> {
+    let mut map = Vec::new();
+    for entry in Object::entries(&Object::from(value.clone())).iter() {
+        let key = Reflect::get_u32(&entry, 0)
+            .ok()
+            .and_then(|key| key.as_string())
+            .unwrap_or_default();
+        let value = Reflect::get_u32(&entry, 1).unwrap_or(JsValue::UNDEFINED);
+        let child = if path.is_empty() {
+            key.clone()
+        } else {
+            format!("{path}.{key}")
+        };
+        if let Some(value) = from_js(&value, &child)? {
+            map.push((key, value));
+        }
+    }
+    Ok(map)
+}
+
+fn read_fields(fields: &JsValue) -> Result<Vec<(String, FormValue)>, String> {
+    if !fields.is_object() || Array::is_array(fields) {
+        return Err("fields must be an object".to_string());
+    }
+    read_map(fields, "")
+}
+
+/// Flattens one field into `key=value` pairs. Arrays repeat the key; objects
+/// become `key[child]` when `nested` is set and are an error otherwise.
+fn flatten(
+    key: &str,
+    value: &FormValue,
+    nested: bool,
+    out: &mut Vec<(String, String)>,
+) -> Result<(), String> {
+    match value {
+        FormValue::Text(text) => out.push((key.to_string(), text.clone())),
+        FormValue::List(items) => {
+            for (index, item) in items.iter().enumerate() {
+                match item {
+                    FormValue::Map(_) if nested => {
+                        flatten(&format!("{key}[{index}]"), item, nested, out)?
+                    }
+                    _ => flatten(key, item, nested, out)?,
+                }
+            }
+        }
+        FormValue::Map(_) if !nested => {
+            return Err(format!(
+                "'{key}' is an object; enable nested flattening to encode it as '{key}[…]'"
+            ));
+        }
+        FormValue::Map(entries) => {
+            for (child, value) in entries {
+                flatten(&format!("{key}[{child}]"), value, nested, out)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn pairs(fields: &[(String, FormValue)], nested: bool) -> Result<Vec<(String, String)>, String> {
+    let mut out = Vec::new();
+    for (key, value) in fields {
+        flatten(key, value, nested, &mut out)?;
+    }
+    Ok(out)
+}
+
+fn encode(fields: &[(String, FormValue)], nested: bool) -> Result<String, String> {
+    let pairs = pairs(fields, nested)?;
+    Ok(form_urlencoded::Serializer::new(String::new())
+        .extend_pairs(pairs)
+        .finish())
+}
+
+/// Decoded fields in first-seen order, each with all of its values.
+fn decode(query: &str) -> Vec<(String, Vec<String>)> {
+    let query = query.trim();
+    let query = query.strip_prefix('?').unwrap_or(query);
+    let mut fields: Vec<(String, Vec<String>)> = Vec::new();
+
+    for (key, value) in form_urlencoded::parse(query.as_bytes()) {
+        match fields.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, values)) => values.push(value.into_owned()),
+            None => fields.push((key.into_owned(), vec![value.into_owned()])),
+        }
+    }
+    fields
+}
+
+/// Length in bytes of `text` once a browser has normalized its line breaks to
+/// CRLF, as it does for multipart field values.
+fn crlf_len(text: &str) -> usize {
+    let mut bytes = text.bytes().peekable();
+    let mut len = 0;
+    while let Some(byte) = bytes.next() {
+        len += match byte {
+            b'\r' => {
+                bytes.next_if_eq(&b'\n');
+                2
+            }
+            b'\n' => 2,
+            _ => 1,
+        };
+    }
+    len
+}
+
+/// Length of a field name inside `name="…"`, where browsers escape `"`, CR and
+/// LF as `%22`, `%0D` and `%0A`.
+fn header_name_len(name: &str) -> usize {
+    name.len()
+        + 2 * name
+            .bytes()
+            .filter(|b| matches!(b, b'"' | b'\r' | b'\n'))
+            .count()
+}
+
+fn multipart_size(
+    fields: &[(String, FormValue)],
+    file_sizes: &[f64],
+    boundary_len: usize,
+) -> Result<f64, String> {
+    // "--" boundary CRLF, then the headers, a blank line, the body and CRLF.
+    let delimiter = 2 + boundary_len + 2;
+    let disposition = "Content-Disposition: form-data; name=\"\"\r\n".len();
+
+    let mut total = 0.0;
+    for (name, value) in pairs(fields, true)? {
+        total +=
+            (delimiter + disposition + header_name_len(&name) + 2 + crlf_len(&value) + 2) as f64;
+    }
+
+    let file_headers =
+        r#"; filename="blob""#.len() + "Content-Type: application/octet-stream\r\n".len();
+    for (index, &size) in file_sizes.iter().enumerate() {
+        if !(size >= 0.0 && size.is_finite() && size.fract() == 0.0) {
+            return Err(format!("file size {index} must be a non-negative integer"));
+        }
+        total += (delimiter + disposition + "file".len() + file_headers + 2 + 2) as f64 + size;
+    }
+
+    // The closing "--" boundary "--" CRLF.
+    Ok(total + (2 + boundary_len + 4) as f64)
+}
+
+/// Serializes `obj` as `application/x-www-form-urlencoded`, like
+/// `URLSearchParams`: spaces become `+`, non-ASCII is percent-encoded as
+/// UTF-8, arrays repeat their key and `null`/`undefined` are skipped. Nested
+/// objects are an error unless `nested` is set, in which case they flatten to
+/// `filter[tag]=x` (array items that are objects to `key[0][child]`).
+#[wasm_bindgen]
+pub fn form_urlencode(obj: JsValue, nested: bool) -> Result<String, JsValue> {
+    read_fields(&obj)
+        .and_then(|fields| encode(&fields, nested))
+        .map_err(|e| JsValue::from_str(&e))
+}
+
+/// Parses a query string (a leading `?` is ignored) into an object. A key that
+/// occurs once maps to its string value, a repeated key to an array of its
+/// values in order, so `form_urlencode` round-trips flat objects of strings.
+#[wasm_bindgen]
+pub fn form_urldecode(qs: &str) -> JsValue {
+    let obj = Object::new();
+    for (key, mut values) in decode(qs) {
+        let value = match values.len() {
+            1 => JsValue::from(values.remove(0)),
+            _ => values.iter().map(JsValue::from).collect::<Array>().into(),
+        };
+        let _ = Reflect::set(&obj, &key.into(), &value);
+    }
+    obj.into()
+}
+
+/// Size in bytes of the `multipart/form-data` body a browser sends for
+/// `fields` (flattened like `form_urlencode` with `nested`) plus one file part
+/// per entry of `file_sizes`, with a boundary of `boundary_len` characters.
+/// Line breaks in values count as CRLF. File parts are counted as
+/// `name="file"; filename="blob"` with an `application/octet-stream` content
+/// type; add the difference in name lengths for exact totals.
+#[wasm_bindgen]
+pub fn estimate_multipart_size(
+    fields: JsValue,
+    file_sizes: &[f64],
+    boundary_len: usize,
+) -> Result<f64, JsValue> {
+    read_fields(&fields)
+        .and_then(|fields| multipart_size(&fields, file_sizes, boundary_len))
+        .map_err(|e| JsValue::from_str(&e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(value: &str) -> FormValue {
+        FormValue::Text(value.to_string())
+    }
+
+    fn fields(entries: &[(&str, FormValue)]) -> Vec<(String, FormValue)> {
+        entries
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn encodes_like_url_search_params() {
+        let form = fields(&[
+            ("q", text("a b&c=d")),
+            ("tag", FormValue::List(vec![text("x"), text("y")])),
+            ("empty", FormValue::List(vec![])),
+            ("größe", text("€")),
+        ]);
+        assert_eq!(
+            encode(&form, false).unwrap(),
+            "q=a+b%26c%3Dd&tag=x&tag=y&gr%C3%B6%C3%9Fe=%E2%82%AC"
+        );
+    }
+
+    #[test]
+    fn flattens_nested_objects_only_when_asked() {
+        let form = fields(&[(
+            "filter",
+            FormValue::Map(vec![
+                ("status".to_string(), text("open")),
+                (
+                    "labels".to_string(),
+                    FormValue::List(vec![
+                        text("bug"),
+                        FormValue::Map(vec![("id".to_string(), text("7"))]),
+                    ]),
+                ),
+            ]),
+        )]);
+        assert_eq!(
+            encode(&form, true).unwrap(),
+            "filter%5Bstatus%5D=open&filter%5Blabels%5D=bug&filter%5Blabels%5D%5B1%5D%5Bid%5D=7"
+        );
+        assert!(encode(&form, false).unwrap_err().contains("'filter'"));
+    }
+
+    #[test]
+    fn flat_objects_round_trip() {
+        let form = fields(&[
+            ("name", text("Zoë O'Brien")),
+            ("note", text("line 1\nline 2 + 100%")),
+            ("empty", text("")),
+            ("ключ", text("значение")),
+            ("tags", FormValue::List(vec![text("a"), text("a b")])),
+        ]);
+        let decoded = decode(&format!("?{}", encode(&form, false).unwrap()));
+        let decoded: Vec<(String, FormValue)> = decoded
+            .into_iter()
+            .map(|(key, values)| match values.as_slice() {
+                [value] => (key, text(value)),
+                _ => (
+                    key,
+                    FormValue::List(values.iter().map(|v| text(v)).collect()),
+                ),
+            })
+            .collect();
+        assert_eq!(decoded, form);
+    }
+
+    #[test]
+    fn decodes_plus_and_repeated_keys() {
+        assert_eq!(
+            decode("a=1+2&b=%E2%82%AC&a=3&flag"),
+            [
+                ("a".to_string(), vec!["1 2".to_string(), "3".to_string()]),
+                ("b".to_string(), vec!["€".to_string()]),
+                ("flag".to_string(), vec![String::new()]),
+            ]
+        );
+        assert!(decode("").is_empty());
+    }
+
+    #[test]
+    fn estimates_multipart_size_exactly() {
+        let boundary = "----WebKitFormBoundary7MA4YWxkTrZu0gW";
+        let form = fields(&[("title", text("Hi\nthere")), ("q\"", text("é"))]);
+        let body = [
+            format!("--{boundary}\r\n"),
+            "Content-Disposition: form-data; name=\"title\"\r\n\r\n".to_string(),
+            "Hi\r\nthere\r\n".to_string(),
+            format!("--{boundary}\r\n"),
+            "Content-Disposition: form-data; name=\"q%22\"\r\n\r\n".to_string(),
+            "é\r\n".to_string(),
+            format!("--{boundary}\r\n"),
+            "Content-Disposition: form-data; name=\"file\"; filename=\"blob\"\r\n".to_string(),
+            "Content-Type: application/octet-stream\r\n\r\n".to_string(),
+            "x".repeat(1000),
+            "\r\n".to_string(),
+            format!("--{boundary}--\r\n"),
+        ]
+        .concat();
+
+        assert_eq!(
+            multipart_size(&form, &[1000.0], boundary.len()).unwrap(),
+            body.len() as f64
+        );
+        assert_eq!(multipart_size(&[], &[], 10).unwrap(), 16.0);
+        assert!(multipart_size(&[], &[-1.0], 10).is_err());
+        assert!(multipart_size(&[], &[f64::NAN], 10).is_err());
+    }
+
+    #[test]
+    fn counts_line_breaks_as_crlf() {
+        assert_eq!(crlf_len("a\nb"), 4);
+        assert_eq!(crlf_len("a\r\nb"), 4);
+        assert_eq!(crlf_len("a\rb"), 4);
+        assert_eq!(crlf_len("\n\r"), 4);
+        assert_eq!(crlf_len("é"), 2);
+    }
+}
@@ -0,0 +1,340 @@
+use std::collections::HashSet;
+
+use js_sys::{Array, Object, Reflect};
+use url::{Position, Url};
+use wasm_bindgen::prelude::*;
+
+use super::read_strings;
+
+/// Checks BCP 47 well-formedness: a language (2–3 letters with up to three
+/// extlangs, or 4–8 letters), then optional script, region, variants,
+/// extensions and private use, e.g. `zh-Hant-TW` or `de-CH-1996`. Tags made
+/// only of private use (`x-…`) are accepted; irregular grandfathered tags
+/// such as `i-klingon` are not.
+fn is_well_formed(tag: &str) -> bool {
+    let subtags: Vec<&str> = tag.split('-').collect();
+    if subtags
+        .iter()
+        .any(|s| s.is_empty() || s.len() > 8 || !s.bytes().all(|b| b.is_ascii_alphanumeric()))
+    {
+        return false;
+    }
+
+    let alpha = |s: &str, range: std::ops::RangeInclusive<usize>| {
+        range.contains(&s.len()) && s.bytes().all(|b| b.is_ascii_alphabetic())
+    };
+    let digits = |s: &str, len: usize| s.len() == len && s.bytes().all(|b| b.is_ascii_digit());
+    let private_use = |rest: &[&str]| !rest.is_empty();
+
+    let mut rest = subtags.as_slice();
+    if rest[0].eq_ignore_ascii_case("x") {
+        return private_use(&rest[1..]);
+    }
+
+    let language = rest[0];
+    if !alpha(language, 2..=3) && !alpha(language, 4..=8) {
+        return false;
+    }
+    rest = &rest[1..];
+    if language.len() <= 3 {
+        let extlangs = rest.iter().take(3).take_while(|s| alpha(s, 3..=3)).count();
+        rest = &rest[extlangs..];
+    }
+    if rest.first().is_some_and(|s| alpha(s, 4..=4)) {
+        rest = &rest[1..];
+    }
+    if rest
+        .first()
+        .is_some_and(|s| alpha(s, 2..=2) || digits(s, 3))
+    {
+        rest = &rest[1..];
+    }
+
+    let mut variants = HashSet::new();
+    while let Some(variant) = rest.first().filter(|s| {
+        (5..=8).contains(&s.len()) || (s.len() == 4 && s.as_bytes()[0].is_ascii_digit())
+    }) {
+        if !variants.insert(variant.to_ascii_lowercase()) {
+            return false;
+        }
+        rest = &rest[1..];
+    }
+
+    let mut singletons = HashSet::new();
+    while let Some(singleton) = rest.first().filter(|s| s.len() == 1) {
+        if singleton.eq_ignore_ascii_case("x") {
+            return private_use(&rest[1..]);
+        }
+        if !singletons.insert(singleton.to_ascii_lowercase()) {
+            return false;
+        }
+        let values = rest[1..].iter().take_while(|s| s.len() >= 2).count();
+        if values == 0 {
+            return false;
+        }
+        rest = &rest[1 + values..];
+    }
+
+    rest.is_empty()
+}
+
+/// A URL or absolute path split around its path, so the locale segment can
+/// be swapped without touching the origin, query or fragment.
+struct Parts {
+    prefix: String,
+    path: String,
+    suffix: String,
+}
+
+impl Parts {
+    fn split(url_or_path: &str) -> Result<Self, String> {
+        let input = url_or_path.trim();
+        if let Ok(url) = Url::parse(input) {
+            if url.cannot_be_a_base() {
+                return Err(format!("'{input}' has no path to localize"));
+            }
+            return Ok(Self {
+                prefix: url[..Position::BeforePath].to_string(),
+                path: url.path().to_string(),
+                suffix: url[Position::AfterPath..].to_string(),
+            });
+        }
+
+        if !input.starts_with('/') || input.starts_with("//") {
+            return Err(format!("'{input}' is neither an absolute URL nor a path"));
+        }
+        let end = input.find(['?', '#']).unwrap_or(input.len());
+        Ok(Self {
+            prefix: String::new(),
+            path: input[..end].to_string(),
+            suffix: input[end..].to_string(),
+        })
+    }
+
+    fn join(&self, path: &str) -> String {
+        format!("{}{path}{}", self.prefix, self.suffix)
+    }
+}
+
+/// Finds the known locale in the first path segment, returning it as listed
+/// and the rest of the path (`""` or starting with `/`). Only whole segments
+/// match, so `/english/` is not `/en/`.
+fn locale_segment<'a, 'p>(path: &'p str, known: &'a [String]) -> Option<(&'a str, &'p str)> {
+    let path = path.strip_prefix('/')?;
+    let end = path.find('/').unwrap_or(path.len());
+    let segment = &path[..end];
+    let locale = known.iter().find(|k| k.eq_ignore_ascii_case(segment))?;
+    Some((locale, &path[end..]))
+}
+
+fn extract(url_or_path: &str, known: &[String]) -> Option<(String, String)> {
+    let parts = Parts::split(url_or_path).ok()?;
+    let (locale, rest) = locale_segment(&parts.path, known)?;
+    let rest = if rest.is_empty() { "/" } else { rest };
+    Some((locale.to_string(), rest.to_string()))
+}
+
+fn set_locale(url: &str, locale: &str, known: &[String]) -> Result<String, String> {
+    let locale = locale.trim();
+    if !is_well_formed(locale) {
+        return Err(format!("'{locale}' is not a well-formed BCP 47 tag"));
+    }
+
+    let parts = Parts::split(url)?;
+    let path = match locale_segment(&parts.path, known) {
+        Some((_, rest)) => format!("/{locale}{rest}"),
+        None => format!("/{locale}{}", parts.path),
+    };
+    Ok(parts.join(&path))
+}
+
+fn alternates(
+    url: &str,
+    locales: &[String],
+    default: &str,
+) -> Result<Vec<(String, String)>, String> {
+    let default = default.trim();
+    if !locales
+        .iter()
+        .any(|l| l.trim().eq_ignore_ascii_case(default))
+    {
+        return Err(format!("default locale '{default}' is not in locales"));
+    }
+
+    let mut seen = HashSet::new();
+    let mut entries = Vec::new();
+    for locale in locales.iter().map(|l| l.trim()) {
+        if seen.insert(locale.to_ascii_lowercase()) {
+            entries.push((locale.to_string(), set_locale(url, locale, locales)?));
+        }
+    }
+    entries.push(("x-default".to_string(), set_locale(url, default, locales)?));
+    Ok(entries)
+}
+
+fn read_locales(values: &Array) -> Vec<String> {
+    read_strings(values)
+        .into_iter()
+        .flatten()
+        .map(|l| l.trim().to_string())
+        .filter(|l| !l.is_empty())
+        .collect()
+}
+
+/// Returns `{locale, rest_path}` when the first path segment of `url_or_path`
+/// is one of `known_locales` (compared case-insensitively, whole segment
+/// only), or `null`. `locale` is spelled as in `known_locales`.
+#[wasm_bindgen]
+pub fn extract_locale_from_path(url_or_path: &str, known_locales: Array) -> JsValue {
+    let Some((locale, rest_path)) = extract(url_or_path, &read_locales(&known_locales)) else {
+        return JsValue::NULL;
+    };
+    let obj = Object::new();
+    let _ = Reflect::set(&obj, &"locale".into(), &locale.into());
+    let _ = Reflect::set(&obj, &"rest_path".into(), &rest_path.into());
+    obj.into()
+}
+
+/// Puts `locale` in the first path segment of `url` (an absolute URL or a
+/// path starting with `/`), replacing a known locale or inserting one. The
+/// query and fragment are preserved.
+#[wasm_bindgen]
+pub fn set_locale_in_path(
+    url: &str,
+    locale: &str,
+    known_locales: Array,
+) -> Result<String, JsValue> {
+    set_locale(url, locale, &read_locales(&known_locales)).map_err(|e| JsValue::from_str(&e))
+}
+
+/// Builds `{hreflang, href}` alternates of `url` for each of `locales`, plus
+/// an `x-default` entry pointing at `default_locale`'s version.
+#[wasm_bindgen]
+pub fn build_hreflang_alternates(
+    url: &str,
+    locales: Array,
+    default_locale: &str,
+) -> Result<Array, JsValue> {
+    let entries = alternates(url, &read_locales(&locales), default_locale)
+        .map_err(|e| JsValue::from_str(&e))?;
+
+    Ok(entries
+        .into_iter()
+        .map(|(hreflang, href)| {
+            let obj = Object::new();
+            let _ = Reflect::set(&obj, &"hreflang".into(), &hreflang.into());
+            let _ = Reflect::set(&obj, &"href".into(), &href.into());
+            JsValue::from(obj)
+        })
+        .collect())
+}
+
+/// Whether `tag` is a well-formed BCP 47 language tag: language, optional
+/// script, region and variants, extensions and private use.
+#[wasm_bindgen]
+pub fn validate_bcp47(tag: &str) -> bool {
+    is_well_formed(tag)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn known(locales: &[&str]) -> Vec<String> {
+        locales.iter().map(|l| l.to_string()).collect()
+    }
+
+    #[test]
+    fn validates_bcp47_tags() {
+        for tag in [
+            "en",
+            "EN-us",
+            "de-CH-1996",
+            "zh-Hant-TW",
+            "sr-Latn",
+            "es-419",
+            "zh-yue-HK",
+            "sl-rozaj-biske",
+            "en-a-bbb-x-a-ccc",
+            "x-whatever",
+            "haw",
+        ] {
+            assert!(is_well_formed(tag), "{tag}");
+        }
+        for tag in [
+            "",
+            "e",
+            "en-",
+            "en--US",
+            "en-toolongsubtag",
+            "en_US",
+            "de-1996-1996",
+            "en-a",
+            "en-a-bb-a-cc",
+            "en-x",
+            "123",
+            "i-klingon",
+        ] {
+            assert!(!is_well_formed(tag), "{tag}");
+        }
+    }
+
+    #[test]
+    fn extracts_whole_segment_locales_case_insensitively() {
+        let locales = known(&["en", "de", "pt-BR"]);
+        assert_eq!(
+            extract("/EN/docs/x", &locales),
+            Some(("en".to_string(), "/docs/x".to_string()))
+        );
+        assert_eq!(
+            extract("https://a.com/pt-br?q=1", &locales),
+            Some(("pt-BR".to_string(), "/".to_string()))
+        );
+        assert_eq!(extract("/english/docs", &locales), None);
+        assert_eq!(extract("/docs/en/x", &locales), None);
+        assert_eq!(extract("docs", &locales), None);
+    }
+
+    #[test]
+    fn replaces_or_inserts_locale_preserving_query_and_fragment() {
+        let locales = known(&["en", "de"]);
+        assert_eq!(
+            set_locale("https://a.com/en/docs/x?v=2#top", "de", &locales).unwrap(),
+            "https://a.com/de/docs/x?v=2#top"
+        );
+        assert_eq!(
+            set_locale("/docs/x?v=2", "de", &locales).unwrap(),
+            "/de/docs/x?v=2"
+        );
+        assert_eq!(
+            set_locale("/english/x", "de", &locales).unwrap(),
+            "/de/english/x"
+        );
+        assert_eq!(set_locale("/en", "de", &locales).unwrap(), "/de");
+        assert_eq!(
+            set_locale("https://a.com", "de", &locales).unwrap(),
+            "https://a.com/de/"
+        );
+        assert!(set_locale("/x", "not a tag", &locales).is_err());
+        assert!(set_locale("relative/x", "de", &locales).is_err());
+        assert!(set_locale("mailto:a@b.com", "de", &locales).is_err());
+    }
+
+    #[test]
+    fn builds_alternates_with_x_default() {
+        let locales = known(&["en", "de", "DE"]);
+        let entries = alternates("https://a.com/en/docs?x=1", &locales, "en").unwrap();
+        assert_eq!(
+            entries,
+            [
+                ("en".to_string(), "https://a.com/en/docs?x=1".to_string()),
+                ("de".to_string(), "https://a.com/de/docs?x=1".to_string()),
+                (
+                    "x-default".to_string(),
+                    "https://a.com/en/docs?x=1".to_string()
+                ),
+            ]
+        );
+        assert!(alternates("https://a.com/", &locales, "fr").is_err());
+    }
+}
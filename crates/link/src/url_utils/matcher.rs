@@ -0,0 +1,312 @@
+use js_sys::Array;
+use url::Url;
+use wasm_bindgen::prelude::*;
+
+use super::read_strings;
+
+#[derive(Clone, Debug, PartialEq)]
+enum HostPattern {
+    Exact(String),
+    /// `*.example.com`: any subdomain, but not `example.com` itself.
+    Subdomains(String),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum PathPattern {
+    Any,
+    Exact(String),
+    Prefix(String),
+}
+
+#[derive(Clone, Debug)]
+struct Pattern {
+    source: String,
+    scheme: Option<String>,
+    host: HostPattern,
+    port: Option<u16>,
+    path: PathPattern,
+}
+
+impl Pattern {
+    fn parse(source: &str) -> Result<Self, String> {
+        let pattern = source.trim();
+        if pattern.is_empty() {
+            return Err("pattern is empty".to_string());
+        }
+        if pattern.contains(['?', '#']) {
+            return Err("queries and fragments are not supported".to_string());
+        }
+
+        let (scheme, rest) = match pattern.split_once("://") {
+            Some((scheme, rest)) => {
+                let valid = scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+                    && scheme
+                        .chars()
+                        .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+                if !valid {
+                    return Err(format!("invalid scheme '{scheme}'"));
+                }
+                (Some(scheme.to_ascii_lowercase()), rest)
+            }
+            None => (None, pattern),
+        };
+
+        let (authority, path) = match rest.find('/') {
+            Some(i) => (&rest[..i], Some(&rest[i..])),
+            None => (rest, None),
+        };
+        if authority.contains('@') {
+            return Err("credentials are not supported".to_string());
+        }
+
+        let (host, port) = split_port(authority)?;
+        let (host, wildcard) = match host.strip_prefix("*.") {
+            Some(domain) => (domain, true),
+            None => (host, false),
+        };
+        if host.contains('*') {
+            return Err("'*' is only allowed as a leading '*.' label".to_string());
+        }
+        // Parsing through the url crate lowercases the host and converts
+        // unicode labels to punycode, exactly as it does for the URLs we match.
+        let host = Url::parse(&format!("http://{host}/"))
+            .ok()
+            .and_then(|url| url.host_str().map(String::from))
+            .filter(|h| !h.is_empty())
+            .ok_or_else(|| format!("invalid host '{host}'"))?;
+
+        let path = match path {
+            None => PathPattern::Any,
+            Some(path) => {
+                let (path, prefix) = match path.strip_suffix('*') {
+                    Some(path) => (path, true),
+                    None => (path, false),
+                };
+                if path.contains('*') {
+                    return Err("'*' is only allowed at the end of the path".to_string());
+                }
+                let mut url = Url::parse("http://host/").expect("static URL is valid");
+                url.set_path(path);
+                if prefix {
+                    PathPattern::Prefix(url.path().to_string())
+                } else {
+                    PathPattern::Exact(url.path().to_string())
+                }
+            }
+        };
+
+        Ok(Self {
+            source: pattern.to_string(),
+            scheme,
+            host: if wildcard {
+                HostPattern::Subdomains(host)
+            } else {
+                HostPattern::Exact(host)
+            },
+            port,
+            path,
+        })
+    }
+
+    fn matches(&self, url: &Url) -> bool {
+        let scheme_ok = match &self.scheme {
+            Some(scheme) => url.scheme() == scheme,
+            None => matches!(url.scheme(), "http" | "https"),
+        };
+        let host_ok = url.host_str().is_some_and(|host| match &self.host {
+            HostPattern::Exact(expected) => host == expected,
+            HostPattern::Subdomains(domain) => host
+                .strip_suffix(domain.as_str())
+                .is_some_and(|sub| sub.len() > 1 && sub.ends_with('.')),
+        });
+        let port_ok = self
+            .port
+            .is_none_or(|port| url.port_or_known_default() == Some(port));
+        let path_ok = match &self.path {
+            PathPattern::Any => true,
+            PathPattern::Exact(path) => url.path() == path,
+            PathPattern::Prefix(prefix) => url.path().starts_with(prefix.as_str()),
+        };
+
+        scheme_ok && host_ok && port_ok && path_ok
+    }
+}
+
+fn split_port(authority: &str) -> Result<(&str, Option<u16>), String> {
+    let port_sep = if authority.starts_with('[') {
+        authority
+            .find(']')
+            .ok_or("unterminated IPv6 address")?
+            .checked_add(1)
+            .filter(|&i| authority[i..].starts_with(':'))
+    } else {
+        authority.rfind(':')
+    };
+
+    match port_sep {
+        Some(i) => {
+            let port = authority[i + 1..]
+                .parse()
+                .map_err(|_| format!("invalid port '{}'", &authority[i + 1..]))?;
+            Ok((&authority[..i], Some(port)))
+        }
+        None => Ok((authority, None)),
+    }
+}
+
+/// Allowlist/denylist of URL patterns such as `*.youtube.com`,
+/// `player.vimeo.com` or `https://codesandbox.io/embed/*`.
+///
+/// A pattern is an optional scheme (patterns without one match `http` and
+/// `https`), a host, an optional port and an optional path. A leading `*.`
+/// matches any subdomain but not the apex itself; list the apex separately if
+/// it should match. A path ending in `*` matches by prefix, any other path
+/// must match exactly. Hosts compare case-insensitively, and unicode and
+/// punycode forms of the same domain are equivalent.
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct UrlMatcher {
+    patterns: Vec<Pattern>,
+}
+
+impl UrlMatcher {
+    fn compile(patterns: &[Option<String>]) -> Result<Self, String> {
+        let mut compiled = Vec::with_capacity(patterns.len());
+        let mut errors = Vec::new();
+
+        for (index, pattern) in patterns.iter().enumerate() {
+            match pattern.as_deref().map(Pattern::parse) {
+                Some(Ok(pattern)) => compiled.push(pattern),
+                Some(Err(reason)) => errors.push(format!("pattern {index}: {reason}")),
+                None => errors.push(format!("pattern {index}: must be a string")),
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(errors.join("; "));
+        }
+        Ok(Self { patterns: compiled })
+    }
+
+    fn find(&self, url: &str) -> Option<&Pattern> {
+        let url = Url::parse(url.trim()).ok()?;
+        self.patterns.iter().find(|pattern| pattern.matches(&url))
+    }
+}
+
+#[wasm_bindgen]
+impl UrlMatcher {
+    /// Compiles `patterns` once. Fails with every invalid pattern's index and
+    /// reason, e.g. `pattern 2: invalid port 'x'`.
+    #[wasm_bindgen(constructor)]
+    pub fn new(patterns: Array) -> Result<UrlMatcher, JsValue> {
+        Self::compile(&read_strings(&patterns)).map_err(|e| JsValue::from_str(&e))
+    }
+
+    /// Whether any pattern matches `url`. Relative or malformed URLs never match.
+    pub fn matches(&self, url: &str) -> bool {
+        self.find(url).is_some()
+    }
+
+    /// The first pattern, as given, that matches `url`.
+    pub fn first_match(&self, url: &str) -> Option<String> {
+        self.find(url).map(|pattern| pattern.source.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matcher(patterns: &[&str]) -> UrlMatcher {
+        let patterns: Vec<_> = patterns.iter().map(|p| Some(p.to_string())).collect();
+        UrlMatcher::compile(&patterns).unwrap()
+    }
+
+    #[test]
+    fn wildcard_matches_subdomains_only() {
+        let m = matcher(&["*.youtube.com"]);
+        assert!(m.matches("https://www.youtube.com/embed/x"));
+        assert!(m.matches("https://a.b.youtube.com/"));
+        assert!(!m.matches("https://youtube.com/"));
+        assert!(!m.matches("https://evilyoutube.com/"));
+        assert!(!m.matches("https://youtube.com.evil.com/"));
+
+        let m = matcher(&["*.youtube.com", "youtube.com"]);
+        assert_eq!(
+            m.first_match("https://youtube.com/").as_deref(),
+            Some("youtube.com")
+        );
+    }
+
+    #[test]
+    fn scheme_is_exact_when_given() {
+        let m = matcher(&["https://player.vimeo.com"]);
+        assert!(m.matches("https://player.vimeo.com/video/1"));
+        assert!(!m.matches("http://player.vimeo.com/video/1"));
+
+        let m = matcher(&["player.vimeo.com"]);
+        assert!(m.matches("http://player.vimeo.com/"));
+        assert!(m.matches("https://player.vimeo.com/"));
+        assert!(!m.matches("ftp://player.vimeo.com/"));
+    }
+
+    #[test]
+    fn path_prefix_and_exact_path() {
+        let m = matcher(&["https://codesandbox.io/embed/*", "example.com/about"]);
+        assert!(m.matches("https://codesandbox.io/embed/abc?view=preview"));
+        assert!(!m.matches("https://codesandbox.io/embed"));
+        assert!(!m.matches("https://codesandbox.io/s/abc"));
+        assert!(m.matches("https://example.com/about#team"));
+        assert!(!m.matches("https://example.com/about/team"));
+    }
+
+    #[test]
+    fn port_must_match_when_specified() {
+        let m = matcher(&["localhost:3000", "https://secure.example.com:443"]);
+        assert!(m.matches("http://localhost:3000/x"));
+        assert!(!m.matches("http://localhost:3001/x"));
+        assert!(!m.matches("http://localhost/x"));
+        assert!(m.matches("https://secure.example.com/"));
+
+        let m = matcher(&["example.com"]);
+        assert!(m.matches("http://example.com:8080/"));
+    }
+
+    #[test]
+    fn hosts_compare_case_insensitively_across_idn_forms() {
+        let m = matcher(&["*.BÜCHER.de", "xn--mnchen-3ya.de"]);
+        assert!(m.matches("https://shop.xn--bcher-kva.de/"));
+        assert!(m.matches("https://SHOP.bücher.de/"));
+        assert!(m.matches("https://münchen.de/"));
+        assert!(m.matches("https://MÜNCHEN.de/"));
+    }
+
+    #[test]
+    fn reports_every_invalid_pattern() {
+        let patterns = vec![
+            Some("ok.com".to_string()),
+            Some("bad.com:http".to_string()),
+            None,
+            Some("a.*.com".to_string()),
+            Some("".to_string()),
+            Some("https://a.com/x*y".to_string()),
+        ];
+        let error = UrlMatcher::compile(&patterns).unwrap_err();
+        assert_eq!(
+            error,
+            "pattern 1: invalid port 'http'; pattern 2: must be a string; \
+             pattern 3: '*' is only allowed as a leading '*.' label; \
+             pattern 4: pattern is empty; \
+             pattern 5: '*' is only allowed at the end of the path"
+        );
+    }
+
+    #[test]
+    fn ignores_unparseable_urls() {
+        let m = matcher(&["example.com"]);
+        assert!(!m.matches("/relative"));
+        assert!(!m.matches("mailto:a@example.com"));
+        assert_eq!(m.first_match("not a url"), None);
+    }
+}
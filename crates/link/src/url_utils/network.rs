@@ -0,0 +1,183 @@
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use js_sys::{Object, Reflect};
+use url::{Host, Url};
+use wasm_bindgen::prelude::*;
+
+/// What the host of a URL says about where a request to it would go.
+#[derive(Debug, PartialEq)]
+struct Target {
+    /// The IP address the host denotes, when it is a literal.
+    ip: Option<IpAddr>,
+    /// Whether the target is loopback, private, link-local or unspecified.
+    private: bool,
+    /// Whether only a DNS lookup could tell where a hostname points.
+    needs_dns: bool,
+}
+
+fn is_private_v4(ip: Ipv4Addr) -> bool {
+    // 0.0.0.0/8 is included because connecting to 0.0.0.0 reaches the local host.
+    ip.is_private() || ip.is_loopback() || ip.is_link_local() || ip.octets()[0] == 0
+}
+
+fn is_private_v6(ip: Ipv6Addr) -> bool {
+    if let Some(v4) = ip.to_ipv4_mapped() {
+        return is_private_v4(v4);
+    }
+    let first = ip.segments()[0];
+    ip.is_loopback()
+        || ip.is_unspecified()
+        // fc00::/7, unique local
+        || first & 0xfe00 == 0xfc00
+        // fe80::/10, link-local
+        || first & 0xffc0 == 0xfe80
+}
+
+fn is_private(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_private_v4(ip),
+        IpAddr::V6(ip) => is_private_v6(ip),
+    }
+}
+
+fn target(url: &str) -> Result<Target, String> {
+    let url = Url::parse(url.trim()).map_err(|e| format!("invalid URL: {e}"))?;
+    // For http(s) and the other special schemes the url crate already decodes
+    // numeric hosts such as `2130706433`, `0x7f.1` or `0177.0.0.1` to an IPv4
+    // address. Other schemes keep the host as written.
+    let ip = match url.host() {
+        None => return Err("URL has no host".to_string()),
+        Some(Host::Ipv4(ip)) => IpAddr::V4(ip),
+        Some(Host::Ipv6(ip)) => IpAddr::V6(ip),
+        Some(Host::Domain(domain)) => match domain.parse() {
+            Ok(ip) => ip,
+            Err(_) => {
+                let domain = domain.trim_end_matches('.').to_ascii_lowercase();
+                // RFC 6761 reserves `localhost` and its subdomains for loopback.
+                let localhost = domain == "localhost" || domain.ends_with(".localhost");
+                return Ok(Target {
+                    ip: None,
+                    private: localhost,
+                    needs_dns: !localhost,
+                });
+            }
+        },
+    };
+    Ok(Target {
+        ip: Some(ip),
+        private: is_private(ip),
+        needs_dns: false,
+    })
+}
+
+/// Whether `url` literally targets a loopback, private (RFC 1918, fc00::/7),
+/// link-local or unspecified address, including IPv4-mapped IPv6 and numeric
+/// IPv4 forms such as `http://2130706433/`. Hostnames other than `localhost`
+/// return `false` because only DNS can tell; use `url_network_target` to tell
+/// that apart from a public address. Fails for URLs without a host.
+#[wasm_bindgen]
+pub fn url_targets_private_network(url: &str) -> Result<bool, JsValue> {
+    target(url)
+        .map(|target| target.private)
+        .map_err(|e| JsValue::from_str(&e))
+}
+
+/// Returns `{ip, private, needs_dns}` for the host of `url`: `ip` is the
+/// normalized address (`null` for hostnames) and `needs_dns` is `true` when
+/// the host is a name that must be resolved before it can be checked.
+#[wasm_bindgen]
+pub fn url_network_target(url: &str) -> Result<JsValue, JsValue> {
+    let target = target(url).map_err(|e| JsValue::from_str(&e))?;
+
+    let ip = target
+        .ip
+        .map_or(JsValue::NULL, |ip| JsValue::from(ip.to_string()));
+    let obj = Object::new();
+    Reflect::set(&obj, &"ip".into(), &ip)?;
+    Reflect::set(&obj, &"private".into(), &target.private.into())?;
+    Reflect::set(&obj, &"needs_dns".into(), &target.needs_dns.into())?;
+    Ok(obj.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn private(url: &str) -> bool {
+        target(url).unwrap().private
+    }
+
+    #[test]
+    fn flags_private_ipv4_literals() {
+        for url in [
+            "http://127.0.0.1/",
+            "http://10.1.2.3/",
+            "http://172.31.255.255/",
+            "http://192.168.0.1:8080/",
+            "http://169.254.169.254/latest/meta-data",
+            "http://0.0.0.0/",
+        ] {
+            assert!(private(url), "{url}");
+        }
+        for url in [
+            "http://8.8.8.8/",
+            "http://172.32.0.1/",
+            "http://192.169.0.1/",
+        ] {
+            assert!(!private(url), "{url}");
+        }
+    }
+
+    #[test]
+    fn decodes_numeric_ipv4_tricks() {
+        for url in [
+            "http://2130706433/",
+            "http://0x7f000001/",
+            "http://0177.0.0.1/",
+            "http://127.1/",
+        ] {
+            let target = target(url).unwrap();
+            assert_eq!(target.ip, Some(IpAddr::V4(Ipv4Addr::LOCALHOST)), "{url}");
+            assert!(target.private, "{url}");
+        }
+    }
+
+    #[test]
+    fn flags_private_ipv6_literals() {
+        for url in [
+            "http://[::1]/",
+            "http://[::]/",
+            "http://[fd12:3456::1]:8080/",
+            "http://[fe80::1]/",
+            "http://[::ffff:127.0.0.1]/",
+            "http://[::ffff:a9fe:a9fe]/",
+        ] {
+            assert!(private(url), "{url}");
+        }
+        for url in ["http://[2606:4700::1111]/", "http://[::ffff:8.8.8.8]/"] {
+            assert!(!private(url), "{url}");
+        }
+    }
+
+    #[test]
+    fn hostnames_need_dns() {
+        let example = target("https://example.com/").unwrap();
+        assert_eq!(
+            example,
+            Target {
+                ip: None,
+                private: false,
+                needs_dns: true
+            }
+        );
+
+        let localhost = target("http://api.LOCALHOST./").unwrap();
+        assert!(localhost.private && !localhost.needs_dns);
+
+        let opaque = target("git://10.0.0.1/repo").unwrap();
+        assert!(opaque.private && !opaque.needs_dns);
+
+        assert!(target("mailto:a@b.com").is_err());
+        assert!(target("not a url").is_err());
+    }
+}
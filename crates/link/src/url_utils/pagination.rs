@@ -0,0 +1,269 @@
+use js_sys::Array;
+use url::{Url, form_urlencoded};
+use wasm_bindgen::prelude::*;
+
+/// Stands for a run of skipped pages in `page_window`.
+const ELLIPSIS: u32 = 0;
+
+/// Sets `param` to `page` in the query of `url`, in place of an existing
+/// value or appended at the end. Page 1 removes the param, so the first page
+/// keeps its canonical URL. Other params keep their order and encoding.
+fn set_page(url: &str, param: &str, page: u32) -> Result<String, String> {
+    if page == 0 {
+        return Err("pages start at 1".to_string());
+    }
+    if param.is_empty() {
+        return Err("page param name must not be empty".to_string());
+    }
+    let mut url = Url::parse(url.trim()).map_err(|e| format!("invalid URL: {e}"))?;
+
+    let is_page = |pair: &str| {
+        form_urlencoded::parse(pair.as_bytes())
+            .next()
+            .is_some_and(|(name, _)| name == param)
+    };
+    let encoded: String = form_urlencoded::Serializer::new(String::new())
+        .append_pair(param, &page.to_string())
+        .finish();
+
+    let mut pairs = Vec::new();
+    let mut placed = page == 1;
+    for pair in url.query().unwrap_or_default().split('&') {
+        if pair.is_empty() {
+            continue;
+        }
+        if !is_page(pair) {
+            pairs.push(pair.to_string());
+        } else if !placed {
+            pairs.push(encoded.clone());
+            placed = true;
+        }
+    }
+    if !placed {
+        pairs.push(encoded);
+    }
+
+    let query = pairs.join("&");
+    url.set_query((!query.is_empty()).then_some(query.as_str()));
+    Ok(url.into())
+}
+
+fn pages(item_count: u32, per_page: u32) -> Result<u32, String> {
+    if per_page == 0 {
+        return Err("per_page must be at least 1".to_string());
+    }
+    Ok(item_count.div_ceil(per_page))
+}
+
+/// The `[start, end)` item indices on `page`, clamped to `item_count`, so
+/// pages past the end are empty rather than an error.
+fn bounds(page: u32, per_page: u32, item_count: u32) -> Result<(u32, u32), String> {
+    if per_page == 0 {
+        return Err("per_page must be at least 1".to_string());
+    }
+    if page == 0 {
+        return Err("pages start at 1".to_string());
+    }
+    let start = (u64::from(page) - 1) * u64::from(per_page);
+    let start = start.min(u64::from(item_count));
+    let end = (start + u64::from(per_page)).min(u64::from(item_count));
+    // Both are at most item_count, so they fit in u32.
+    Ok((start as u32, end as u32))
+}
+
+/// Pages to render around `current`: the first and last page, `width` pages
+/// on each side of `current`, and `ELLIPSIS` for each gap. A gap of a single
+/// page shows that page instead, since an ellipsis would take the same room.
+fn window(current: u32, total: u32, width: u32) -> Vec<u32> {
+    if total == 0 {
+        return Vec::new();
+    }
+    let current = current.clamp(1, total);
+    let low = current.saturating_sub(width).max(1);
+    let high = current.saturating_add(width).min(total);
+
+    let mut shown = Vec::new();
+    if low > 1 {
+        shown.push(1);
+        match low {
+            2 => {}
+            3 => shown.push(2),
+            _ => shown.push(ELLIPSIS),
+        }
+    }
+    shown.extend(low..=high);
+    if high < total {
+        match total - high {
+            1 => {}
+            2 => shown.push(total - 1),
+            _ => shown.push(ELLIPSIS),
+        }
+        shown.push(total);
+    }
+    shown
+}
+
+fn to_array(numbers: &[u32]) -> Array {
+    numbers.iter().map(|&n| JsValue::from(n)).collect()
+}
+
+/// Returns `url` with its `param` query param set to `page`, or removed for
+/// page 1. Page 0 is an error.
+#[wasm_bindgen]
+pub fn with_page_param(url: &str, param: &str, page: u32) -> Result<String, JsValue> {
+    set_page(url, param, page).map_err(|e| JsValue::from_str(&e))
+}
+
+/// URL of the page after `current`, or `None` on the last page, when there
+/// are no pages, or when `current` is already past `total`.
+#[wasm_bindgen]
+pub fn next_page_url(
+    url: &str,
+    param: &str,
+    current: u32,
+    total: u32,
+) -> Result<Option<String>, JsValue> {
+    if current >= total {
+        return Ok(None);
+    }
+    with_page_param(url, param, current.max(1) + 1).map(Some)
+}
+
+/// URL of the page before `current`, or `None` on the first page. From past
+/// the end it points at the last page, so the link leads back to results.
+#[wasm_bindgen]
+pub fn prev_page_url(
+    url: &str,
+    param: &str,
+    current: u32,
+    total: u32,
+) -> Result<Option<String>, JsValue> {
+    let previous = current.saturating_sub(1).min(total);
+    if previous == 0 {
+        return Ok(None);
+    }
+    with_page_param(url, param, previous).map(Some)
+}
+
+/// Page numbers to render for a pager, with 0 marking an ellipsis:
+/// `page_window(6, 20, 2)` is `[1, 0, 4, 5, 6, 7, 8, 0, 20]`. `current` is
+/// clamped into `1..=total`; no pages give an empty array.
+#[wasm_bindgen]
+pub fn page_window(current: u32, total: u32, width: u32) -> Array {
+    to_array(&window(current, total, width))
+}
+
+/// Number of pages needed for `item_count` items; 0 items need 0 pages.
+/// `per_page` of 0 is an error.
+#[wasm_bindgen]
+pub fn total_pages(item_count: u32, per_page: u32) -> Result<u32, JsValue> {
+    pages(item_count, per_page).map_err(|e| JsValue::from_str(&e))
+}
+
+/// `[start_index, end_index_exclusive]` of the items on the 1-based `page`.
+/// Pages past the end give an empty `[item_count, item_count]`.
+#[wasm_bindgen]
+pub fn page_bounds(page: u32, per_page: u32, item_count: u32) -> Result<Array, JsValue> {
+    let (start, end) = bounds(page, per_page, item_count).map_err(|e| JsValue::from_str(&e))?;
+    Ok(to_array(&[start, end]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sets_and_removes_the_page_param() {
+        let url = "https://example.com/list?sort=new&page=3&q=caf%C3%A9+au+lait#top";
+        assert_eq!(
+            set_page(url, "page", 4).unwrap(),
+            "https://example.com/list?sort=new&page=4&q=caf%C3%A9+au+lait#top"
+        );
+        assert_eq!(
+            set_page(url, "page", 1).unwrap(),
+            "https://example.com/list?sort=new&q=caf%C3%A9+au+lait#top"
+        );
+        assert_eq!(
+            set_page("https://example.com/list?page=2", "page", 1).unwrap(),
+            "https://example.com/list"
+        );
+        assert_eq!(
+            set_page("https://example.com/list", "p", 2).unwrap(),
+            "https://example.com/list?p=2"
+        );
+        assert_eq!(
+            set_page("https://example.com/?page=2&page=5", "page", 3).unwrap(),
+            "https://example.com/?page=3"
+        );
+        assert!(set_page("https://example.com/", "page", 0).is_err());
+        assert!(set_page("/relative", "page", 2).is_err());
+    }
+
+    #[test]
+    fn counts_pages() {
+        assert_eq!(pages(0, 10), Ok(0));
+        assert_eq!(pages(1, 10), Ok(1));
+        assert_eq!(pages(10, 10), Ok(1));
+        assert_eq!(pages(11, 10), Ok(2));
+        assert_eq!(pages(u32::MAX, 1), Ok(u32::MAX));
+        assert!(pages(10, 0).is_err());
+    }
+
+    #[test]
+    fn computes_page_bounds() {
+        assert_eq!(bounds(1, 10, 25), Ok((0, 10)));
+        assert_eq!(bounds(3, 10, 25), Ok((20, 25)));
+        // Exactly per_page items: one full page, then nothing.
+        assert_eq!(bounds(1, 10, 10), Ok((0, 10)));
+        assert_eq!(bounds(2, 10, 10), Ok((10, 10)));
+        assert_eq!(bounds(1, 10, 0), Ok((0, 0)));
+        assert_eq!(bounds(u32::MAX, u32::MAX, 5), Ok((5, 5)));
+        assert!(bounds(1, 0, 10).is_err());
+        assert!(bounds(0, 10, 10).is_err());
+    }
+
+    #[test]
+    fn builds_page_windows() {
+        assert_eq!(window(6, 20, 2), [1, 0, 4, 5, 6, 7, 8, 0, 20]);
+        assert_eq!(window(1, 20, 2), [1, 2, 3, 0, 20]);
+        assert_eq!(window(20, 20, 2), [1, 0, 18, 19, 20]);
+        // A one-page gap shows the page rather than an ellipsis.
+        assert_eq!(window(4, 7, 1), [1, 2, 3, 4, 5, 6, 7]);
+        assert_eq!(window(4, 8, 1), [1, 2, 3, 4, 5, 0, 8]);
+        assert_eq!(window(5, 10, 1), [1, 0, 4, 5, 6, 0, 10]);
+        assert_eq!(window(3, 5, 0), [1, 2, 3, 4, 5]);
+        assert_eq!(window(1, 1, 2), [1]);
+        assert_eq!(window(1, 0, 2), Vec::<u32>::new());
+        // Past the end is treated as the last page.
+        assert_eq!(window(30, 20, 1), [1, 0, 19, 20]);
+        assert_eq!(window(0, 3, 0), [1, 2, 3]);
+        assert_eq!(window(2, u32::MAX, 1), [1, 2, 3, 0, u32::MAX]);
+    }
+
+    #[test]
+    fn links_to_neighbouring_pages() {
+        let url = "https://example.com/list?page=2";
+        let next = |current, total| next_page_url(url, "page", current, total).unwrap();
+        let prev = |current, total| prev_page_url(url, "page", current, total).unwrap();
+
+        assert_eq!(
+            next(2, 3).as_deref(),
+            Some("https://example.com/list?page=3")
+        );
+        assert_eq!(next(3, 3), None);
+        assert_eq!(next(5, 3), None);
+        assert_eq!(next(1, 0), None);
+
+        assert_eq!(prev(2, 3).as_deref(), Some("https://example.com/list"));
+        assert_eq!(
+            prev(3, 3).as_deref(),
+            Some("https://example.com/list?page=2")
+        );
+        assert_eq!(prev(1, 3), None);
+        assert_eq!(
+            prev(5, 3).as_deref(),
+            Some("https://example.com/list?page=3")
+        );
+        assert_eq!(prev(2, 0), None);
+    }
+}
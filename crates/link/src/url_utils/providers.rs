@@ -0,0 +1,513 @@
+use js_sys::{Array, Object, Reflect};
+use url::Url;
+use wasm_bindgen::prelude::*;
+
+use super::{encode_segment, read_strings};
+
+/// Longest URL every browser, mail client and proxy on the way is known to
+/// accept. Calendar links with long details pass it easily.
+const SAFE_URL_LENGTH: usize = 2048;
+
+/// Google's servers answer longer URLs with 414 URI Too Long.
+const GOOGLE_MAX_URL_LENGTH: usize = 8192;
+
+/// Builds `base` with the given query params, leaving out empty values.
+fn with_params(base: &str, params: &[(&str, &str)]) -> String {
+    let mut url = Url::parse(base).expect("provider base URLs are valid");
+    {
+        let mut query = url.query_pairs_mut();
+        for (name, value) in params.iter().filter(|(_, value)| !value.is_empty()) {
+            query.append_pair(name, value);
+        }
+    }
+    if url.query() == Some("") {
+        url.set_query(None);
+    }
+    url.into()
+}
+
+fn clean_list(values: &[Option<String>]) -> Vec<String> {
+    values
+        .iter()
+        .flatten()
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+        .collect()
+}
+
+fn encode_address(address: &str) -> Result<String, String> {
+    let valid = address.split('@').count() == 2
+        && address.split('@').all(|part| !part.is_empty())
+        && !address.contains(|c: char| c.is_whitespace() || c.is_control());
+    if !valid {
+        return Err(format!("invalid email address '{address}'"));
+    }
+    Ok(address
+        .split('@')
+        .map(encode_segment)
+        .collect::<Vec<_>>()
+        .join("@"))
+}
+
+fn encode_addresses(addresses: &[String]) -> Result<String, String> {
+    addresses
+        .iter()
+        .map(|address| encode_address(address))
+        .collect::<Result<Vec<_>, _>>()
+        .map(|encoded| encoded.join(","))
+}
+
+/// RFC 6068 line breaks are CRLF, so lone `\n` and `\r` are widened first.
+fn crlf(text: &str) -> String {
+    text.replace("\r\n", "\n")
+        .replace('\r', "\n")
+        .replace('\n', "\r\n")
+}
+
+fn mailto(
+    to: &[String],
+    subject: &str,
+    body: &str,
+    cc: &[String],
+    bcc: &[String],
+) -> Result<String, String> {
+    let mut headers = Vec::new();
+    for (name, value) in [
+        ("subject", subject.trim().to_string()),
+        ("body", crlf(body)),
+    ] {
+        if !value.trim().is_empty() {
+            headers.push(format!("{name}={}", encode_segment(&value)));
+        }
+    }
+    for (name, addresses) in [("cc", cc), ("bcc", bcc)] {
+        if !addresses.is_empty() {
+            headers.push(format!("{name}={}", encode_addresses(addresses)?));
+        }
+    }
+
+    let mut link = format!("mailto:{}", encode_addresses(to)?);
+    if !headers.is_empty() {
+        link.push('?');
+        link.push_str(&headers.join("&"));
+    }
+    Ok(link)
+}
+
+fn google_maps(
+    lat: f64,
+    lon: f64,
+    zoom: Option<u8>,
+    query: Option<&str>,
+) -> Result<String, String> {
+    if !(lat.is_finite() && (-90.0..=90.0).contains(&lat)) {
+        return Err(format!("latitude {lat} is outside -90..=90"));
+    }
+    if !(lon.is_finite() && (-180.0..=180.0).contains(&lon)) {
+        return Err(format!("longitude {lon} is outside -180..=180"));
+    }
+    if let Some(zoom) = zoom.filter(|&zoom| zoom > 21) {
+        return Err(format!("zoom {zoom} is outside 0..=21"));
+    }
+
+    let center = format!("{lat},{lon}");
+    let query = query.map(str::trim).unwrap_or_default();
+    Ok(match zoom {
+        Some(zoom) if query.is_empty() => with_params(
+            "https://www.google.com/maps/@",
+            &[
+                ("api", "1"),
+                ("map_action", "map"),
+                ("center", &center),
+                ("zoom", &zoom.to_string()),
+            ],
+        ),
+        _ => with_params(
+            "https://www.google.com/maps/search/",
+            &[
+                ("api", "1"),
+                ("query", if query.is_empty() { &center } else { query }),
+            ],
+        ),
+    })
+}
+
+/// Reduces `phone` to the digits of an E.164 number: an optional leading `+`
+/// or `00`, then 8 to 15 digits not starting with 0. Spaces, dashes, dots and
+/// parentheses are ignored. This only checks the E.164 shape; whether the
+/// country code or the number length is valid for that country isn't known.
+fn e164_digits(phone: &str) -> Result<String, String> {
+    let compact: String = phone
+        .chars()
+        .filter(|c| !matches!(c, ' ' | '-' | '.' | '(' | ')'))
+        .collect();
+    let digits = compact
+        .strip_prefix('+')
+        .or_else(|| compact.strip_prefix("00"))
+        .unwrap_or(&compact);
+    let valid = (8..=15).contains(&digits.len())
+        && digits.bytes().all(|b| b.is_ascii_digit())
+        && !digits.starts_with('0');
+    if !valid {
+        return Err(format!(
+            "'{phone}' is not an international phone number like +41 44 668 18 00"
+        ));
+    }
+    Ok(digits.to_string())
+}
+
+fn whatsapp(phone: &str, text: &str) -> Result<String, String> {
+    let digits = e164_digits(phone)?;
+    Ok(with_params(
+        &format!("https://wa.me/{digits}"),
+        &[("text", text.trim())],
+    ))
+}
+
+/// Inverse of `days_from_civil`: the proleptic Gregorian date of a day count
+/// since 1970-01-01.
+fn civil_from_days(days: i64) -> (i64, i64, i64) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// Formats epoch milliseconds as the basic ISO 8601 UTC form Google Calendar
+/// expects, e.g. `20240315T093000Z`. Sub-second precision is dropped.
+fn basic_iso(ms: f64) -> Result<String, String> {
+    // Years 0 to 9999, so the year is always four digits.
+    const RANGE: std::ops::RangeInclusive<f64> = -62_167_219_200_000.0..=253_402_300_799_999.0;
+    if !(ms.is_finite() && RANGE.contains(&ms)) {
+        return Err(format!("{ms} is not a timestamp in years 0 to 9999"));
+    }
+    let seconds = (ms / 1000.0).floor() as i64;
+    let (days, time) = (seconds.div_euclid(86_400), seconds.rem_euclid(86_400));
+    let (year, month, day) = civil_from_days(days);
+    Ok(format!(
+        "{year:04}{month:02}{day:02}T{:02}{:02}{:02}Z",
+        time / 3600,
+        time / 60 % 60,
+        time % 60
+    ))
+}
+
+#[derive(Debug, PartialEq)]
+struct CalendarLink {
+    url: String,
+    warnings: Vec<String>,
+}
+
+fn google_calendar(
+    title: &str,
+    start_ms: f64,
+    end_ms: f64,
+    details: &str,
+    location: &str,
+) -> Result<CalendarLink, String> {
+    if end_ms < start_ms {
+        return Err("the event ends before it starts".to_string());
+    }
+    let dates = format!("{}/{}", basic_iso(start_ms)?, basic_iso(end_ms)?);
+    let url = with_params(
+        "https://calendar.google.com/calendar/render",
+        &[
+            ("action", "TEMPLATE"),
+            ("text", title.trim()),
+            ("dates", &dates),
+            ("details", details.trim()),
+            ("location", location.trim()),
+        ],
+    );
+
+    let mut warnings = Vec::new();
+    if url.len() > GOOGLE_MAX_URL_LENGTH {
+        warnings.push(format!(
+            "URL is {} characters; Google rejects URLs over {GOOGLE_MAX_URL_LENGTH}, shorten the details",
+            url.len()
+        ));
+    } else if url.len() > SAFE_URL_LENGTH {
+        warnings.push(format!(
+            "URL is {} characters; some browsers and mail clients cut URLs over {SAFE_URL_LENGTH}",
+            url.len()
+        ));
+    }
+    Ok(CalendarLink { url, warnings })
+}
+
+fn tweet_intent(text: &str, url: &str, hashtags: &[String]) -> Result<String, String> {
+    let url = url.trim();
+    if !url.is_empty() && Url::parse(url).is_err() {
+        return Err(format!("'{url}' is not an absolute URL"));
+    }
+    let mut tags = Vec::new();
+    for tag in hashtags {
+        let tag = tag.trim_start_matches('#');
+        if tag.contains(|c: char| c.is_whitespace() || c == ',' || c == '#') {
+            return Err(format!(
+                "hashtag '{tag}' can't contain spaces, commas or '#'"
+            ));
+        }
+        if !tag.is_empty() {
+            tags.push(tag);
+        }
+    }
+    Ok(with_params(
+        "https://twitter.com/intent/tweet",
+        &[
+            ("text", text.trim()),
+            ("url", url),
+            ("hashtags", &tags.join(",")),
+        ],
+    ))
+}
+
+/// Builds a `mailto:` link for the addresses in `to`, `cc` and `bcc` (empty
+/// entries are skipped). Subject and body are percent-encoded per RFC 6068,
+/// with line breaks in the body sent as `%0D%0A`; empty fields are left out.
+#[wasm_bindgen]
+pub fn build_mailto(
+    to: Array,
+    subject: &str,
+    body: &str,
+    cc: Array,
+    bcc: Array,
+) -> Result<String, JsValue> {
+    mailto(
+        &clean_list(&read_strings(&to)),
+        subject,
+        body,
+        &clean_list(&read_strings(&cc)),
+        &clean_list(&read_strings(&bcc)),
+    )
+    .map_err(|e| JsValue::from_str(&e))
+}
+
+/// Builds a Google Maps link in the Maps URLs format. With a `zoom` it opens
+/// the map centered on `lat,lon`; otherwise it drops a pin there. A non-empty
+/// `query` searches for that place instead, since Maps URLs can't combine a
+/// search with a center, and the coordinates are only validated.
+#[wasm_bindgen]
+pub fn build_google_maps_link(
+    lat: f64,
+    lon: f64,
+    zoom: Option<u8>,
+    query: Option<String>,
+) -> Result<String, JsValue> {
+    google_maps(lat, lon, zoom, query.as_deref()).map_err(|e| JsValue::from_str(&e))
+}
+
+/// Builds a `https://wa.me/` chat link. `phone` must be an international
+/// number (`+41 44 668 18 00` or `0041…`); only its E.164 shape is checked,
+/// not the numbering plan of the country. An empty `text` is left out.
+#[wasm_bindgen]
+pub fn build_whatsapp_link(phone: &str, text: &str) -> Result<String, JsValue> {
+    whatsapp(phone, text).map_err(|e| JsValue::from_str(&e))
+}
+
+/// Builds a Google Calendar "add event" link. Times are epoch milliseconds,
+/// written as UTC basic ISO 8601 (`20240315T093000Z`); empty `details` and
+/// `location` are left out.
+#[wasm_bindgen]
+pub fn build_google_calendar_link(
+    title: &str,
+    start_ms: f64,
+    end_ms: f64,
+    details: &str,
+    location: &str,
+) -> Result<String, JsValue> {
+    google_calendar(title, start_ms, end_ms, details, location)
+        .map(|link| link.url)
+        .map_err(|e| JsValue::from_str(&e))
+}
+
+/// Like `build_google_calendar_link`, but returns `{url, length, warnings}`
+/// where `warnings` explains when the URL is long enough to be cut or
+/// rejected.
+#[wasm_bindgen]
+pub fn build_google_calendar_link_detailed(
+    title: &str,
+    start_ms: f64,
+    end_ms: f64,
+    details: &str,
+    location: &str,
+) -> Result<JsValue, JsValue> {
+    let link = google_calendar(title, start_ms, end_ms, details, location)
+        .map_err(|e| JsValue::from_str(&e))?;
+    let obj = Object::new();
+    Reflect::set(&obj, &"length".into(), &(link.url.len() as f64).into())?;
+    Reflect::set(&obj, &"url".into(), &link.url.into())?;
+    Reflect::set(
+        &obj,
+        &"warnings".into(),
+        &link
+            .warnings
+            .into_iter()
+            .map(JsValue::from)
+            .collect::<Array>(),
+    )?;
+    Ok(obj.into())
+}
+
+/// Builds a tweet intent link. `hashtags` may be given with or without `#`;
+/// empty text, URL and hashtags are left out.
+#[wasm_bindgen]
+pub fn build_tweet_intent(text: &str, url: &str, hashtags: Array) -> Result<String, JsValue> {
+    tweet_intent(text, url, &clean_list(&read_strings(&hashtags)))
+        .map_err(|e| JsValue::from_str(&e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn list(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn builds_mailto_links() {
+        assert_eq!(
+            mailto(
+                &list(&["a@example.com", "b+news@example.com"]),
+                "Hi & welcome",
+                "Line 1\nLine 2",
+                &list(&["c@example.com"]),
+                &[],
+            )
+            .unwrap(),
+            "mailto:a@example.com,b%2Bnews@example.com\
+             ?subject=Hi%20%26%20welcome&body=Line%201%0D%0ALine%202&cc=c@example.com"
+        );
+        assert_eq!(
+            mailto(&list(&["a@example.com"]), " ", "", &[], &[]).unwrap(),
+            "mailto:a@example.com"
+        );
+        assert_eq!(
+            mailto(&[], "Hi", "", &[], &[]).unwrap(),
+            "mailto:?subject=Hi"
+        );
+        assert!(mailto(&list(&["not an address"]), "", "", &[], &[]).is_err());
+        assert!(mailto(&[], "", "", &[], &list(&["a@b@c"])).is_err());
+    }
+
+    #[test]
+    fn builds_google_maps_links() {
+        assert_eq!(
+            google_maps(47.3769, 8.5417, None, None).unwrap(),
+            "https://www.google.com/maps/search/?api=1&query=47.3769%2C8.5417"
+        );
+        assert_eq!(
+            google_maps(47.3769, 8.5417, Some(12), Some(" ")).unwrap(),
+            "https://www.google.com/maps/@?api=1&map_action=map&center=47.3769%2C8.5417&zoom=12"
+        );
+        assert_eq!(
+            google_maps(0.0, 0.0, Some(12), Some("Café Zürich")).unwrap(),
+            "https://www.google.com/maps/search/?api=1&query=Caf%C3%A9+Z%C3%BCrich"
+        );
+        assert!(google_maps(91.0, 0.0, None, None).is_err());
+        assert!(google_maps(0.0, f64::NAN, None, None).is_err());
+        assert!(google_maps(0.0, 0.0, Some(22), None).is_err());
+    }
+
+    #[test]
+    fn checks_e164_numbers() {
+        assert_eq!(e164_digits("+41 44 668 18 00").unwrap(), "41446681800");
+        assert_eq!(e164_digits("0041-(44)-668.18.00").unwrap(), "41446681800");
+        assert_eq!(e164_digits("14155552671").unwrap(), "14155552671");
+        for phone in [
+            "",
+            "+",
+            "044 668 18 00",
+            "+41 44 668 18 00 00000",
+            "+1 555",
+            "+41 44 CALL ME",
+        ] {
+            assert!(e164_digits(phone).is_err(), "{phone}");
+        }
+    }
+
+    #[test]
+    fn builds_whatsapp_links() {
+        assert_eq!(
+            whatsapp("+41 44 668 18 00", "Hello there!\nSee you").unwrap(),
+            "https://wa.me/41446681800?text=Hello+there%21%0ASee+you"
+        );
+        assert_eq!(
+            whatsapp("+41446681800", "").unwrap(),
+            "https://wa.me/41446681800"
+        );
+        assert!(whatsapp("12345", "hi").is_err());
+    }
+
+    #[test]
+    fn formats_basic_iso_dates() {
+        assert_eq!(basic_iso(0.0).unwrap(), "19700101T000000Z");
+        assert_eq!(basic_iso(1_710_495_000_999.0).unwrap(), "20240315T093000Z");
+        assert_eq!(basic_iso(951_782_400_000.0).unwrap(), "20000229T000000Z");
+        assert_eq!(basic_iso(-1.0).unwrap(), "19691231T235959Z");
+        assert!(basic_iso(f64::INFINITY).is_err());
+        assert!(basic_iso(1e17).is_err());
+    }
+
+    #[test]
+    fn builds_google_calendar_links() {
+        let link =
+            google_calendar("Standup", 1_710_495_000_000.0, 1_710_496_800_000.0, "", " ").unwrap();
+        assert_eq!(
+            link.url,
+            "https://calendar.google.com/calendar/render?action=TEMPLATE&text=Standup\
+             &dates=20240315T093000Z%2F20240315T100000Z"
+        );
+        assert!(link.warnings.is_empty());
+
+        let link = google_calendar("", 0.0, 0.0, "Agenda:\n1. Intro", "Room 4").unwrap();
+        assert!(
+            link.url
+                .ends_with("&details=Agenda%3A%0A1.+Intro&location=Room+4"),
+            "{}",
+            link.url
+        );
+        assert!(!link.url.contains("text="));
+
+        assert!(google_calendar("x", 10.0, 0.0, "", "").is_err());
+    }
+
+    #[test]
+    fn warns_about_long_calendar_links() {
+        let long = google_calendar("x", 0.0, 0.0, &"a".repeat(3000), "").unwrap();
+        assert_eq!(long.warnings.len(), 1);
+        assert!(long.warnings[0].contains(&SAFE_URL_LENGTH.to_string()));
+
+        let too_long = google_calendar("x", 0.0, 0.0, &"é".repeat(2000), "").unwrap();
+        assert!(too_long.url.len() > GOOGLE_MAX_URL_LENGTH);
+        assert!(too_long.warnings[0].contains(&GOOGLE_MAX_URL_LENGTH.to_string()));
+    }
+
+    #[test]
+    fn builds_tweet_intents() {
+        assert_eq!(
+            tweet_intent(
+                "Read this",
+                "https://example.com/a?b=1",
+                &list(&["#rust", "wasm"])
+            )
+            .unwrap(),
+            "https://twitter.com/intent/tweet?text=Read+this\
+             &url=https%3A%2F%2Fexample.com%2Fa%3Fb%3D1&hashtags=rust%2Cwasm"
+        );
+        assert_eq!(
+            tweet_intent("", "", &list(&["#"])).unwrap(),
+            "https://twitter.com/intent/tweet"
+        );
+        assert!(tweet_intent("", "", &list(&["two words"])).is_err());
+        assert!(tweet_intent("", "/relative", &[]).is_err());
+    }
+}
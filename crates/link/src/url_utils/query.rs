@@ -0,0 +1,592 @@
+use js_sys::{Array, Object, Reflect};
+use url::Url;
+use wasm_bindgen::prelude::*;
+
+/// Largest integer a JS number represents exactly.
+const MAX_SAFE_INTEGER: i64 = (1 << 53) - 1;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Kind {
+    String,
+    Int,
+    Float,
+    Bool,
+    Date,
+    StringList,
+    IntList,
+}
+
+impl Kind {
+    fn parse(name: &str) -> Option<Self> {
+        Some(match name {
+            "string" => Self::String,
+            "int" => Self::Int,
+            "float" => Self::Float,
+            "bool" => Self::Bool,
+            "date" => Self::Date,
+            "string[]" => Self::StringList,
+            "int[]" => Self::IntList,
+            _ => return None,
+        })
+    }
+
+    fn expected(self) -> &'static str {
+        match self {
+            Self::String => "a string",
+            Self::Int => "an integer",
+            Self::Float => "a number",
+            Self::Bool => "true/false, 1/0 or yes/no",
+            Self::Date => "a date (YYYY-MM-DD or RFC 3339)",
+            Self::StringList => "a list of strings",
+            Self::IntList => "a list of integers",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Field {
+    name: String,
+    kind: Kind,
+    required: bool,
+    has_default: bool,
+}
+
+#[derive(Debug, PartialEq)]
+enum Typed {
+    Text(String),
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    /// Milliseconds since the Unix epoch.
+    Date(f64),
+    TextList(Vec<String>),
+    IntList(Vec<i64>),
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct Problems {
+    missing: Vec<String>,
+    /// `(field, value, expected)`.
+    invalid: Vec<(String, String, &'static str)>,
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct Parsed {
+    fields: Vec<(String, Typed)>,
+    /// Fields that were absent but have a default, for the caller to fill in.
+    defaulted: Vec<String>,
+    /// Parameters not in the schema, with all their values.
+    unknown: Vec<(String, Vec<String>)>,
+}
+
+/// Query parameters of an absolute URL, a relative URL or a bare query
+/// string with or without its `?`, grouped by name in first-seen order.
+fn query_params(url_or_qs: &str) -> Vec<(String, Vec<String>)> {
+    let input = url_or_qs.trim();
+    let url = Url::parse(input).or_else(|_| {
+        let base = Url::parse("http://query.invalid/")?;
+        if input.contains('?') || input.starts_with(['/', '#']) {
+            base.join(input)
+        } else {
+            base.join(&format!("?{input}"))
+        }
+    });
+
+    let mut params: Vec<(String, Vec<String>)> = Vec::new();
+    for (key, value) in url.iter().flat_map(Url::query_pairs) {
+        match params.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, values)) => values.push(value.into_owned()),
+            None => params.push((key.into_owned(), vec![value.into_owned()])),
+        }
+    }
+    params
+}
+
+fn parse_int(value: &str) -> Option<i64> {
+    value
+        .trim()
+        .parse::<i64>()
+        .ok()
+        .filter(|n| n.abs() <= MAX_SAFE_INTEGER)
+}
+
+fn parse_bool(value: &str) -> Option<bool> {
+    match value.trim().to_ascii_lowercase().as_str() {
+        "true" | "1" | "yes" => Some(true),
+        "false" | "0" | "no" => Some(false),
+        _ => None,
+    }
+}
+
+/// Days since 1970-01-01 of a proleptic Gregorian date.
+fn days_from_civil(year: i64, month: i64, day: i64) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146_097 + day_of_era - 719_468
+}
+
+fn days_in_month(year: i64, month: i64) -> i64 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Parses `YYYY-MM-DD` (midnight UTC) or an RFC 3339 date-time such as
+/// `2024-01-01T12:30:00.5+02:00` into epoch milliseconds. A date-time without
+/// an offset is taken as UTC.
+fn parse_date(value: &str) -> Option<f64> {
+    let value = value.trim();
+    let number = |s: &str, len: usize| {
+        (s.len() == len && s.bytes().all(|b| b.is_ascii_digit()))
+            .then(|| s.parse::<i64>().ok())
+            .flatten()
+    };
+
+    let (date, time) = match value.split_once(['T', 't', ' ']) {
+        Some((date, time)) => (date, Some(time)),
+        None => (value, None),
+    };
+    let mut parts = date.split('-');
+    let year = number(parts.next()?, 4)?;
+    let month = number(parts.next()?, 2)?;
+    let day = number(parts.next()?, 2)?;
+    if parts.next().is_some() || !(1..=12).contains(&month) {
+        return None;
+    }
+    if !(1..=days_in_month(year, month)).contains(&day) {
+        return None;
+    }
+    let mut ms = days_from_civil(year, month, day) * 86_400_000;
+
+    if let Some(time) = time {
+        let (clock, offset) = match time.find(['Z', 'z', '+', '-']) {
+            Some(i) => time.split_at(i),
+            None => (time, ""),
+        };
+        let (clock, fraction) = clock.split_once('.').unwrap_or((clock, ""));
+        let mut parts = clock.split(':');
+        let hour = number(parts.next()?, 2)?;
+        let minute = number(parts.next()?, 2)?;
+        let second = parts.next().map_or(Some(0), |s| number(s, 2))?;
+        if parts.next().is_some() || hour > 23 || minute > 59 || second > 60 {
+            return None;
+        }
+        if !fraction.bytes().all(|b| b.is_ascii_digit())
+            || (clock.len() < 8 && !fraction.is_empty())
+        {
+            return None;
+        }
+        let millis = format!("{fraction:0<3}")[..3].parse::<i64>().ok()?;
+        ms += ((hour * 60 + minute) * 60 + second) * 1000 + millis;
+
+        match offset {
+            "" | "Z" | "z" => {}
+            _ => {
+                let sign = if offset.starts_with('-') { -1 } else { 1 };
+                let (hours, minutes) = offset[1..].split_once(':')?;
+                let hours = number(hours, 2)?;
+                let minutes = number(minutes, 2)?;
+                if hours > 23 || minutes > 59 {
+                    return None;
+                }
+                ms -= sign * (hours * 60 + minutes) * 60_000;
+            }
+        }
+    }
+    Some(ms as f64)
+}
+
+/// Splits repeated and comma-separated values into one list, dropping empty
+/// items.
+fn list_items(values: &[String]) -> impl Iterator<Item = &str> {
+    values
+        .iter()
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+}
+
+fn coerce(kind: Kind, values: &[String]) -> Result<Typed, String> {
+    // Scalars take the first value, like `URLSearchParams.get`.
+    let first = values[0].as_str();
+    let invalid = || first.to_string();
+    Ok(match kind {
+        Kind::String => Typed::Text(first.to_string()),
+        Kind::Int => Typed::Int(parse_int(first).ok_or_else(invalid)?),
+        Kind::Float => Typed::Float(
+            first
+                .trim()
+                .parse::<f64>()
+                .ok()
+                .filter(|n| n.is_finite())
+                .ok_or_else(invalid)?,
+        ),
+        Kind::Bool => Typed::Bool(parse_bool(first).ok_or_else(invalid)?),
+        Kind::Date => Typed::Date(parse_date(first).ok_or_else(invalid)?),
+        Kind::StringList => Typed::TextList(list_items(values).map(String::from).collect()),
+        Kind::IntList => Typed::IntList(
+            list_items(values)
+                .map(|item| parse_int(item).ok_or_else(|| item.to_string()))
+                .collect::<Result<_, _>>()?,
+        ),
+    })
+}
+
+/// Coerces the parameters of `url_or_qs` according to `schema`, collecting
+/// every missing and invalid field instead of stopping at the first. An empty
+/// value counts as absent for every type but `string`.
+fn parse_typed(url_or_qs: &str, schema: &[Field], strict: bool) -> Result<Parsed, Problems> {
+    let mut params = query_params(url_or_qs);
+    let mut parsed = Parsed::default();
+    let mut problems = Problems::default();
+
+    for field in schema {
+        let values = params
+            .iter()
+            .position(|(key, _)| *key == field.name)
+            .map(|i| params.remove(i).1)
+            .filter(|values| field.kind == Kind::String || list_items(values).next().is_some());
+
+        match values {
+            Some(values) => match coerce(field.kind, &values) {
+                Ok(value) => parsed.fields.push((field.name.clone(), value)),
+                Err(value) => {
+                    problems
+                        .invalid
+                        .push((field.name.clone(), value, field.kind.expected()))
+                }
+            },
+            None if field.has_default => parsed.defaulted.push(field.name.clone()),
+            None if field.required => problems.missing.push(field.name.clone()),
+            None => {}
+        }
+    }
+
+    if !problems.missing.is_empty() || !problems.invalid.is_empty() {
+        return Err(problems);
+    }
+    if !strict {
+        parsed.unknown = params;
+    }
+    Ok(parsed)
+}
+
+/// Default values from the schema, by field name.
+type Defaults = Vec<(String, JsValue)>;
+
+/// Reads `{name: "int"}` or `{name: {type: "int", required?, default?}}`,
+/// returning the fields and their defaults.
+fn read_schema(schema: &JsValue) -> Result<(Vec<Field>, Defaults), String> {
+    if !schema.is_object() || Array::is_array(schema) {
+        return Err("schema must be an object".to_string());
+    }
+
+    let mut fields = Vec::new();
+    let mut defaults = Vec::new();
+    for entry in Object::entries(&Object::from(schema.clone())).iter() {
+        let name = Reflect::get_u32(&entry, 0)
+            .ok()
+            .and_then(|name| name.as_string())
+            .unwrap_or_default();
+        let spec = Reflect::get_u32(&entry, 1).unwrap_or(JsValue::UNDEFINED);
+        let get = |key: &str| Reflect::get(&spec, &key.into()).unwrap_or(JsValue::UNDEFINED);
+
+        let (type_name, required, default) = match spec.as_string() {
+            Some(type_name) => (Some(type_name), false, JsValue::UNDEFINED),
+            None if spec.is_object() => (
+                get("type").as_string(),
+                get("required").as_bool().unwrap_or(false),
+                get("default"),
+            ),
+            None => (None, false, JsValue::UNDEFINED),
+        };
+        let kind = type_name
+            .as_deref()
+            .and_then(Kind::parse)
+            .ok_or_else(|| {
+                format!(
+                    "schema field '{name}': type must be one of string, int, float, bool, date, string[], int[]"
+                )
+            })?;
+
+        let has_default = !default.is_undefined();
+        if has_default {
+            defaults.push((name.clone(), default));
+        }
+        fields.push(Field {
+            name,
+            kind,
+            required,
+            has_default,
+        });
+    }
+    Ok((fields, defaults))
+}
+
+fn typed_to_js(value: Typed) -> JsValue {
+    match value {
+        Typed::Text(text) => text.into(),
+        Typed::Int(n) => (n as f64).into(),
+        Typed::Float(n) | Typed::Date(n) => n.into(),
+        Typed::Bool(flag) => flag.into(),
+        Typed::TextList(items) => items.iter().map(JsValue::from).collect::<Array>().into(),
+        Typed::IntList(items) => items
+            .iter()
+            .map(|&n| JsValue::from(n as f64))
+            .collect::<Array>()
+            .into(),
+    }
+}
+
+fn problems_to_js(problems: Problems) -> JsValue {
+    let mut summary = Vec::new();
+    if !problems.missing.is_empty() {
+        summary.push(format!("missing {}", problems.missing.join(", ")));
+    }
+    if !problems.invalid.is_empty() {
+        let names: Vec<&str> = problems
+            .invalid
+            .iter()
+            .map(|(f, _, _)| f.as_str())
+            .collect();
+        summary.push(format!("invalid {}", names.join(", ")));
+    }
+
+    let error = js_sys::Error::new(&format!("query parameters: {}", summary.join("; ")));
+    let missing: Array = problems.missing.iter().map(JsValue::from).collect();
+    let invalid: Array = problems
+        .invalid
+        .into_iter()
+        .map(|(field, value, expected)| {
+            let obj = Object::new();
+            let _ = Reflect::set(&obj, &"field".into(), &field.into());
+            let _ = Reflect::set(&obj, &"value".into(), &value.into());
+            let _ = Reflect::set(&obj, &"expected".into(), &expected.into());
+            JsValue::from(obj)
+        })
+        .collect();
+    let _ = Reflect::set(&error, &"missing".into(), &missing);
+    let _ = Reflect::set(&error, &"invalid".into(), &invalid);
+    error.into()
+}
+
+/// Parses the query of `url_or_qs` (a URL or a query string) into an object
+/// typed by `schema`, which maps parameter names to `"string"`, `"int"`,
+/// `"float"`, `"bool"`, `"date"`, `"string[]"` or `"int[]"`, or to
+/// `{type, required, default}`.
+///
+/// Bools accept true/false, 1/0 and yes/no in any case. Dates become epoch
+/// milliseconds. Lists accept repeated keys and comma-separated values. Absent
+/// fields take their default, if any. Unknown parameters are passed through as
+/// strings (arrays when repeated) unless `strict`, which drops them.
+///
+/// Missing required and invalid fields are reported together: the thrown
+/// `Error` has `missing: [name]` and `invalid: [{field, value, expected}]`.
+#[wasm_bindgen]
+pub fn parse_query_typed(
+    url_or_qs: &str,
+    schema: JsValue,
+    strict: bool,
+) -> Result<JsValue, JsValue> {
+    let (fields, defaults) = read_schema(&schema).map_err(|e| JsValue::from_str(&e))?;
+    let parsed = parse_typed(url_or_qs, &fields, strict).map_err(problems_to_js)?;
+
+    let obj = Object::new();
+    for (name, values) in parsed.unknown {
+        let value = match values.as_slice() {
+            [value] => JsValue::from(value),
+            _ => values.iter().map(JsValue::from).collect::<Array>().into(),
+        };
+        Reflect::set(&obj, &name.into(), &value)?;
+    }
+    for (name, value) in parsed.fields {
+        Reflect::set(&obj, &name.into(), &typed_to_js(value))?;
+    }
+    for (name, default) in defaults {
+        if parsed.defaulted.contains(&name) {
+            Reflect::set(&obj, &name.into(), &default)?;
+        }
+    }
+    Ok(obj.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field(name: &str, kind: &str) -> Field {
+        Field {
+            name: name.to_string(),
+            kind: Kind::parse(kind).unwrap(),
+            required: false,
+            has_default: false,
+        }
+    }
+
+    fn required(name: &str, kind: &str) -> Field {
+        Field {
+            required: true,
+            ..field(name, kind)
+        }
+    }
+
+    #[test]
+    fn reads_query_from_urls_and_query_strings() {
+        let expected = [
+            ("a".to_string(), vec!["1 2".to_string(), "3".to_string()]),
+            ("b".to_string(), vec!["é".to_string()]),
+        ];
+        for input in [
+            "https://example.com/x?a=1+2&b=%C3%A9&a=3#frag",
+            "/x?a=1+2&b=%C3%A9&a=3",
+            "?a=1+2&b=%C3%A9&a=3",
+            "a=1+2&b=%C3%A9&a=3",
+        ] {
+            assert_eq!(query_params(input), expected, "{input}");
+        }
+        assert!(query_params("https://example.com/").is_empty());
+    }
+
+    #[test]
+    fn coerces_scalars() {
+        let schema = [
+            field("q", "string"),
+            field("page", "int"),
+            field("ratio", "float"),
+            field("a", "bool"),
+            field("b", "bool"),
+            field("c", "bool"),
+            field("since", "date"),
+        ];
+        let parsed = parse_typed(
+            "q=x&page=42&ratio=1.5e1&a=YES&b=0&c=False&since=2024-01-01",
+            &schema,
+            true,
+        )
+        .unwrap();
+        assert_eq!(
+            parsed.fields,
+            [
+                ("q".to_string(), Typed::Text("x".to_string())),
+                ("page".to_string(), Typed::Int(42)),
+                ("ratio".to_string(), Typed::Float(15.0)),
+                ("a".to_string(), Typed::Bool(true)),
+                ("b".to_string(), Typed::Bool(false)),
+                ("c".to_string(), Typed::Bool(false)),
+                ("since".to_string(), Typed::Date(1_704_067_200_000.0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn parses_dates_and_date_times() {
+        assert_eq!(parse_date("1970-01-01"), Some(0.0));
+        assert_eq!(parse_date("2024-02-29"), Some(1_709_164_800_000.0));
+        assert_eq!(
+            parse_date("2024-01-01T12:30:00.5Z"),
+            Some(1_704_112_200_500.0)
+        );
+        assert_eq!(
+            parse_date("2024-01-01T14:30+02:00"),
+            Some(1_704_112_200_000.0)
+        );
+        assert_eq!(parse_date("1969-12-31"), Some(-86_400_000.0));
+        for bad in [
+            "2023-02-29",
+            "2024-13-01",
+            "2024-1-1",
+            "24-01-01",
+            "2024-01-01T25:00",
+            "today",
+        ] {
+            assert_eq!(parse_date(bad), None, "{bad}");
+        }
+    }
+
+    #[test]
+    fn lists_accept_repeated_and_comma_separated_values() {
+        let schema = [field("tags", "string[]"), field("ids", "int[]")];
+        let parsed = parse_typed("tags=a,b&tags=c&ids=1,2&ids=3,&ids=", &schema, true).unwrap();
+        assert_eq!(
+            parsed.fields,
+            [
+                (
+                    "tags".to_string(),
+                    Typed::TextList(vec!["a".into(), "b".into(), "c".into()])
+                ),
+                ("ids".to_string(), Typed::IntList(vec![1, 2, 3])),
+            ]
+        );
+    }
+
+    #[test]
+    fn reports_all_missing_and_invalid_fields() {
+        let schema = [
+            required("id", "int"),
+            required("from", "date"),
+            field("page", "int"),
+            field("ids", "int[]"),
+            field("on", "bool"),
+            required("q", "string"),
+        ];
+        let problems = parse_typed("from=&page=two&ids=1,x&on=maybe", &schema, false).unwrap_err();
+        assert_eq!(problems.missing, ["id", "from", "q"]);
+        assert_eq!(
+            problems.invalid,
+            [
+                ("page".to_string(), "two".to_string(), "an integer"),
+                ("ids".to_string(), "x".to_string(), "a list of integers"),
+                (
+                    "on".to_string(),
+                    "maybe".to_string(),
+                    "true/false, 1/0 or yes/no"
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn defaults_and_unknown_parameters() {
+        let schema = [
+            Field {
+                has_default: true,
+                ..required("page", "int")
+            },
+            field("q", "string"),
+        ];
+        let parsed = parse_typed("q=&utm_source=x&utm_source=y&ref=z", &schema, false).unwrap();
+        assert_eq!(parsed.defaulted, ["page"]);
+        assert_eq!(
+            parsed.fields,
+            [("q".to_string(), Typed::Text(String::new()))]
+        );
+        assert_eq!(
+            parsed.unknown,
+            [
+                (
+                    "utm_source".to_string(),
+                    vec!["x".to_string(), "y".to_string()]
+                ),
+                ("ref".to_string(), vec!["z".to_string()]),
+            ]
+        );
+
+        let strict = parse_typed("q=&utm_source=x", &schema, true).unwrap();
+        assert!(strict.unknown.is_empty());
+    }
+
+    #[test]
+    fn rejects_out_of_range_numbers() {
+        assert_eq!(parse_int("9007199254740991"), Some(MAX_SAFE_INTEGER));
+        assert_eq!(parse_int("9007199254740992"), None);
+        assert_eq!(parse_int("1.5"), None);
+        let problems = parse_typed("x=inf", &[field("x", "float")], true).unwrap_err();
+        assert_eq!(problems.invalid.len(), 1);
+    }
+}
@@ -0,0 +1,372 @@
+use std::collections::HashSet;
+
+use js_sys::{Array, Object, Reflect};
+use url::Url;
+use wasm_bindgen::prelude::*;
+
+use super::{Ignore, canonical_url, registrable_domain};
+
+const DEFAULT_MAX_HOPS: usize = 20;
+
+struct Hop {
+    url: String,
+    status: u16,
+    location: Option<String>,
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct Chain {
+    final_url: String,
+    hop_count: usize,
+    loop_detected: bool,
+    downgraded_to_http: bool,
+    crossed_origin: bool,
+    truncated: bool,
+}
+
+fn is_redirect(status: u16) -> bool {
+    matches!(status, 300..=303 | 307 | 308)
+}
+
+/// Loops are detected on the URL without its fragment; unlike other canonical
+/// comparisons the trailing slash counts, since `/a` → `/a/` is a real hop.
+fn loop_key(url: &Url) -> String {
+    canonical_url(
+        url,
+        Ignore {
+            fragment: true,
+            query: false,
+            trailing_slash: false,
+        },
+    )
+}
+
+fn resolve_chain(hops: &[Hop], max_hops: usize) -> Result<Chain, String> {
+    let first = hops.first().ok_or("hops must not be empty")?;
+    let mut current =
+        Url::parse(first.url.trim()).map_err(|e| format!("hop 0: invalid url: {e}"))?;
+    let origin = current.origin();
+
+    let mut chain = Chain::default();
+    let mut visited = HashSet::from([loop_key(&current)]);
+
+    for (index, hop) in hops.iter().enumerate() {
+        // A hop normally records the URL the previous one led to; it may be
+        // relative to it, and if it differs it still counts toward loops.
+        let url = current
+            .join(hop.url.trim())
+            .map_err(|e| format!("hop {index}: invalid url: {e}"))?;
+        let moved = loop_key(&url) != loop_key(&current);
+        if moved && !visited.insert(loop_key(&url)) {
+            chain.loop_detected = true;
+            current = url;
+            break;
+        }
+
+        let Some(location) = hop.location.as_deref().filter(|_| is_redirect(hop.status)) else {
+            current = url;
+            break;
+        };
+        if chain.hop_count == max_hops {
+            chain.truncated = true;
+            current = url;
+            break;
+        }
+
+        let target = url
+            .join(location.trim())
+            .map_err(|e| format!("hop {index}: invalid location: {e}"))?;
+        chain.hop_count += 1;
+        chain.downgraded_to_http |= url.scheme() == "https" && target.scheme() == "http";
+        chain.crossed_origin |= target.origin() != origin;
+
+        let repeated = !visited.insert(loop_key(&target));
+        current = target;
+        if repeated {
+            chain.loop_detected = true;
+            break;
+        }
+    }
+
+    chain.final_url = current.to_string();
+    Ok(chain)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum Source {
+    Canonical,
+    OgUrl,
+    FinalUrl,
+}
+
+impl Source {
+    fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "canonical" => Some(Self::Canonical),
+            "og:url" | "og_url" => Some(Self::OgUrl),
+            "final_url" => Some(Self::FinalUrl),
+            _ => None,
+        }
+    }
+}
+
+fn pick(candidates: &[(Source, String)]) -> Option<String> {
+    let final_url = candidates
+        .iter()
+        .find(|(source, _)| *source == Source::FinalUrl)
+        .and_then(|(_, url)| Url::parse(url.trim()).ok());
+    let final_domain = final_url
+        .as_ref()
+        .and_then(|url| url.host())
+        .map(|host| registrable_domain(&host));
+
+    let mut ranked: Vec<&(Source, String)> = candidates.iter().collect();
+    ranked.sort_by_key(|(source, _)| *source);
+
+    ranked.into_iter().find_map(|(source, href)| {
+        let mut url = match &final_url {
+            Some(base) => base.join(href.trim()).ok()?,
+            None => Url::parse(href.trim()).ok()?,
+        };
+        if !matches!(url.scheme(), "http" | "https") {
+            return None;
+        }
+        let domain = url.host().map(|host| registrable_domain(&host));
+        if *source != Source::FinalUrl && final_domain.is_some() && domain != final_domain {
+            return None;
+        }
+        url.set_fragment(None);
+        Some(url.to_string())
+    })
+}
+
+fn read_hop(value: &JsValue, index: usize) -> Result<Hop, String> {
+    let field = |name: &str| Reflect::get(value, &name.into()).unwrap_or(JsValue::UNDEFINED);
+
+    let url = field("url")
+        .as_string()
+        .ok_or_else(|| format!("hop {index}: url must be a string"))?;
+    let status = field("status")
+        .as_f64()
+        .filter(|s| s.fract() == 0.0 && (100.0..=599.0).contains(s))
+        .ok_or_else(|| format!("hop {index}: status must be an HTTP status code"))?;
+
+    Ok(Hop {
+        url,
+        status: status as u16,
+        location: field("location")
+            .as_string()
+            .filter(|l| !l.trim().is_empty()),
+    })
+}
+
+/// Collapses a recorded redirect chain of `{url, status, location}` hops into
+/// `{final_url, hop_count, loop_detected, downgraded_to_http, crossed_origin,
+/// truncated}`. Each `location` is resolved against its hop's URL, and the
+/// chain ends at the first hop that is not a 3xx with a location.
+///
+/// Loops are detected by canonical comparison (ignoring fragments) and stop
+/// the walk. Chains with more than `max_hops` redirects (default 20) stop
+/// early with `truncated` set. `crossed_origin` is set when any redirect
+/// leaves the first hop's origin. No requests are made.
+#[wasm_bindgen]
+pub fn resolve_redirect_chain(hops: Array, max_hops: Option<usize>) -> Result<JsValue, JsValue> {
+    let hops = hops
+        .iter()
+        .enumerate()
+        .map(|(index, value)| read_hop(&value, index))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| JsValue::from_str(&e))?;
+    let chain = resolve_chain(&hops, max_hops.unwrap_or(DEFAULT_MAX_HOPS))
+        .map_err(|e| JsValue::from_str(&e))?;
+
+    let obj = Object::new();
+    Reflect::set(&obj, &"final_url".into(), &chain.final_url.into())?;
+    Reflect::set(&obj, &"hop_count".into(), &(chain.hop_count as u32).into())?;
+    Reflect::set(&obj, &"loop_detected".into(), &chain.loop_detected.into())?;
+    Reflect::set(
+        &obj,
+        &"downgraded_to_http".into(),
+        &chain.downgraded_to_http.into(),
+    )?;
+    Reflect::set(&obj, &"crossed_origin".into(), &chain.crossed_origin.into())?;
+    Reflect::set(&obj, &"truncated".into(), &chain.truncated.into())?;
+    Ok(obj.into())
+}
+
+/// Picks the canonical URL from `{source, url}` candidates, where `source` is
+/// `"canonical"` (rel=canonical), `"og:url"` or `"final_url"` (where the
+/// redirect chain ended).
+///
+/// Precedence is canonical, then og:url, then final_url. Candidates are
+/// resolved against final_url when given and must be http(s). When final_url
+/// is known, canonical and og:url must share its registrable domain, so a
+/// page can't claim to be another site. The result has no fragment. Entries
+/// with an unknown source or a non-string url are skipped.
+#[wasm_bindgen]
+pub fn pick_canonical(candidates: Array) -> Option<String> {
+    let candidates: Vec<(Source, String)> = candidates
+        .iter()
+        .filter_map(|value| {
+            let field = |name: &str| Reflect::get(&value, &name.into()).ok()?.as_string();
+            Some((Source::parse(&field("source")?)?, field("url")?))
+        })
+        .collect();
+    pick(&candidates)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hop(url: &str, status: u16, location: Option<&str>) -> Hop {
+        Hop {
+            url: url.to_string(),
+            status,
+            location: location.map(String::from),
+        }
+    }
+
+    #[test]
+    fn follows_relative_locations() {
+        let chain = resolve_chain(
+            &[
+                hop(
+                    "http://example.com/old",
+                    301,
+                    Some("https://example.com/old"),
+                ),
+                hop("https://example.com/old", 302, Some("/new/")),
+                hop("https://example.com/new/", 200, None),
+            ],
+            DEFAULT_MAX_HOPS,
+        )
+        .unwrap();
+        assert_eq!(
+            chain,
+            Chain {
+                final_url: "https://example.com/new/".to_string(),
+                hop_count: 2,
+                crossed_origin: true,
+                ..Chain::default()
+            }
+        );
+    }
+
+    #[test]
+    fn final_url_is_the_last_location_when_unfetched() {
+        let chain = resolve_chain(
+            &[hop("https://a.com/x", 307, Some("y?z=1"))],
+            DEFAULT_MAX_HOPS,
+        )
+        .unwrap();
+        assert_eq!(chain.final_url, "https://a.com/y?z=1");
+        assert_eq!(chain.hop_count, 1);
+        assert!(!chain.crossed_origin);
+    }
+
+    #[test]
+    fn trailing_slash_redirect_is_not_a_loop() {
+        let chain = resolve_chain(
+            &[
+                hop("https://a.com/docs", 301, Some("/docs/")),
+                hop("https://a.com/docs/", 200, None),
+            ],
+            DEFAULT_MAX_HOPS,
+        )
+        .unwrap();
+        assert!(!chain.loop_detected);
+        assert_eq!(chain.final_url, "https://a.com/docs/");
+    }
+
+    #[test]
+    fn detects_loops_canonically() {
+        let chain = resolve_chain(
+            &[
+                hop("https://a.com/1", 302, Some("/2")),
+                hop("https://a.com/2", 302, Some("HTTPS://A.COM:443/1#again")),
+            ],
+            DEFAULT_MAX_HOPS,
+        )
+        .unwrap();
+        assert!(chain.loop_detected);
+        assert_eq!(chain.hop_count, 2);
+    }
+
+    #[test]
+    fn flags_downgrades() {
+        let chain = resolve_chain(
+            &[hop("https://a.com/", 301, Some("http://a.com/"))],
+            DEFAULT_MAX_HOPS,
+        )
+        .unwrap();
+        assert!(chain.downgraded_to_http);
+        assert!(chain.crossed_origin);
+    }
+
+    #[test]
+    fn reports_truncation() {
+        let hops: Vec<Hop> = (0..30)
+            .map(|i| {
+                hop(
+                    &format!("https://a.com/{i}"),
+                    302,
+                    Some(&format!("/{}", i + 1)),
+                )
+            })
+            .collect();
+        let chain = resolve_chain(&hops, DEFAULT_MAX_HOPS).unwrap();
+        assert!(chain.truncated);
+        assert_eq!(chain.hop_count, 20);
+        assert_eq!(chain.final_url, "https://a.com/20");
+
+        let chain = resolve_chain(&hops[..3], 3).unwrap();
+        assert!(!chain.truncated);
+    }
+
+    #[test]
+    fn rejects_bad_input() {
+        assert!(resolve_chain(&[], DEFAULT_MAX_HOPS).is_err());
+        assert!(resolve_chain(&[hop("/relative", 200, None)], DEFAULT_MAX_HOPS).is_err());
+    }
+
+    fn candidates(entries: &[(Source, &str)]) -> Vec<(Source, String)> {
+        entries.iter().map(|(s, u)| (*s, u.to_string())).collect()
+    }
+
+    #[test]
+    fn canonical_wins_over_og_and_final() {
+        let picked = pick(&candidates(&[
+            (Source::FinalUrl, "https://www.example.com/a?utm=1"),
+            (Source::OgUrl, "https://example.com/og"),
+            (Source::Canonical, "/a#top"),
+        ]));
+        assert_eq!(picked.as_deref(), Some("https://www.example.com/a"));
+    }
+
+    #[test]
+    fn skips_candidates_on_other_domains() {
+        let picked = pick(&candidates(&[
+            (Source::Canonical, "https://evil.com/a"),
+            (Source::OgUrl, "https://cdn.example.com/a"),
+            (Source::FinalUrl, "https://www.example.com/a"),
+        ]));
+        assert_eq!(picked.as_deref(), Some("https://cdn.example.com/a"));
+
+        let picked = pick(&candidates(&[
+            (Source::Canonical, "javascript:alert(1)"),
+            (Source::FinalUrl, "https://www.example.com/a#x"),
+        ]));
+        assert_eq!(picked.as_deref(), Some("https://www.example.com/a"));
+    }
+
+    #[test]
+    fn without_final_url_takes_first_absolute_candidate() {
+        let picked = pick(&candidates(&[
+            (Source::Canonical, "/relative"),
+            (Source::OgUrl, "https://example.com/og"),
+        ]));
+        assert_eq!(picked.as_deref(), Some("https://example.com/og"));
+        assert_eq!(pick(&[]), None);
+    }
+}
@@ -0,0 +1,484 @@
+use std::collections::{HashMap, HashSet};
+
+use js_sys::{Array, Object, Reflect};
+use url::Url;
+use wasm_bindgen::prelude::*;
+
+use super::{encode_segment, percent_decode};
+
+mod constraint;
+
+use constraint::Constraint;
+
+/// Name under which the rest of the path matched by `*` is reported.
+const WILDCARD: &str = "*";
+
+#[derive(Clone, Debug, PartialEq)]
+enum Segment {
+    Literal(String),
+    Param {
+        name: String,
+        optional: bool,
+        constraint: Option<Constraint>,
+    },
+    Wildcard,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Route {
+    segments: Vec<Segment>,
+}
+
+/// Splits `:name(constraint)?` into its parts.
+fn parse_param(segment: &str) -> Result<Segment, String> {
+    let body = &segment[1..];
+    let (body, optional) = match body.strip_suffix('?') {
+        Some(body) => (body, true),
+        None => (body, false),
+    };
+    let (name, constraint) = match body.find('(') {
+        Some(open) => {
+            let source = body[open + 1..]
+                .strip_suffix(')')
+                .ok_or_else(|| format!("segment '{segment}': unclosed constraint"))?;
+            (&body[..open], Some(Constraint::parse(source)?))
+        }
+        None => (body, None),
+    };
+
+    let valid = name.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if !valid {
+        return Err(format!(
+            "segment '{segment}': invalid parameter name '{name}'"
+        ));
+    }
+    Ok(Segment::Param {
+        name: name.to_string(),
+        optional,
+        constraint,
+    })
+}
+
+/// Splits a path on `/` into decoded segments, without the leading slash and
+/// ignoring one trailing slash.
+fn path_segments(path: &str) -> Option<Vec<String>> {
+    let path = path.strip_prefix('/')?;
+    let path = path.strip_suffix('/').unwrap_or(path);
+    if path.is_empty() {
+        return Some(Vec::new());
+    }
+    path.split('/').map(percent_decode).collect()
+}
+
+impl Route {
+    fn parse(pattern: &str) -> Result<Self, String> {
+        let pattern = pattern.trim();
+        if !pattern.starts_with('/') {
+            return Err(format!("pattern '{pattern}' must start with '/'"));
+        }
+
+        let body = pattern[1..].strip_suffix('/').unwrap_or(&pattern[1..]);
+        let raw = if body.is_empty() {
+            Vec::new()
+        } else {
+            split_pattern(body)
+        };
+        let mut segments = Vec::new();
+        let mut names = HashSet::new();
+
+        for (index, text) in raw.iter().enumerate() {
+            let segment = match *text {
+                "*" if index + 1 == raw.len() => Segment::Wildcard,
+                "*" => return Err("'*' is only allowed as the last segment".to_string()),
+                s if s.starts_with(':') => parse_param(s)?,
+                s if s.contains(['?', '#']) => {
+                    return Err(format!(
+                        "pattern '{pattern}' must not have a query or fragment"
+                    ));
+                }
+                s => Segment::Literal(
+                    percent_decode(s).ok_or_else(|| format!("segment '{s}' is not valid UTF-8"))?,
+                ),
+            };
+            if let Segment::Param { name, .. } = &segment
+                && !names.insert(name.clone())
+            {
+                return Err(format!("parameter '{name}' appears twice"));
+            }
+            segments.push(segment);
+        }
+        Ok(Self { segments })
+    }
+
+    fn exec(&self, path: &str) -> Option<Vec<(String, String)>> {
+        let segments = path_segments(&path_of(path)?)?;
+        let mut params = Vec::new();
+        self.match_from(0, &segments, 0, &mut params)
+            .then_some(params)
+    }
+
+    fn match_from(
+        &self,
+        index: usize,
+        path: &[String],
+        at: usize,
+        params: &mut Vec<(String, String)>,
+    ) -> bool {
+        let Some(segment) = self.segments.get(index) else {
+            return at == path.len();
+        };
+        match segment {
+            Segment::Literal(literal) => {
+                path.get(at) == Some(literal) && self.match_from(index + 1, path, at + 1, params)
+            }
+            Segment::Wildcard => {
+                params.push((WILDCARD.to_string(), path[at..].join("/")));
+                true
+            }
+            Segment::Param {
+                name,
+                optional,
+                constraint,
+            } => {
+                let fits = path.get(at).is_some_and(|value| {
+                    !value.is_empty() && constraint.as_ref().is_none_or(|c| c.is_match(value))
+                });
+                if fits {
+                    params.push((name.clone(), path[at].clone()));
+                    if self.match_from(index + 1, path, at + 1, params) {
+                        return true;
+                    }
+                    params.pop();
+                }
+                *optional && self.match_from(index + 1, path, at, params)
+            }
+        }
+    }
+
+    fn build(&self, params: &HashMap<String, String>) -> Result<String, String> {
+        let mut path = String::new();
+        for segment in &self.segments {
+            match segment {
+                Segment::Literal(literal) => {
+                    path.push('/');
+                    path.push_str(&encode_segment(literal));
+                }
+                Segment::Wildcard => {
+                    for part in params.get(WILDCARD).iter().flat_map(|rest| rest.split('/')) {
+                        if !part.is_empty() {
+                            path.push('/');
+                            path.push_str(&encode_segment(part));
+                        }
+                    }
+                }
+                Segment::Param {
+                    name,
+                    optional,
+                    constraint,
+                } => match params.get(name).filter(|value| !value.is_empty()) {
+                    Some(value) => {
+                        if let Some(constraint) = constraint.as_ref().filter(|c| !c.is_match(value))
+                        {
+                            return Err(format!(
+                                "param '{name}' = '{value}' does not match ({})",
+                                constraint.source()
+                            ));
+                        }
+                        path.push('/');
+                        path.push_str(&encode_segment(value));
+                    }
+                    None if *optional => {}
+                    None => return Err(format!("missing required param '{name}'")),
+                },
+            }
+        }
+        if path.is_empty() {
+            path.push('/');
+        }
+        Ok(path)
+    }
+}
+
+/// Splits a pattern body on the `/` that separate segments, leaving slashes
+/// inside a `(…)` constraint alone.
+fn split_pattern(body: &str) -> Vec<&str> {
+    let mut segments = Vec::new();
+    let mut depth = 0usize;
+    let mut escaped = false;
+    let mut start = 0;
+    for (i, c) in body.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            '/' if depth == 0 => {
+                segments.push(&body[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    segments.push(&body[start..]);
+    segments
+}
+
+/// The path of `path_or_url`: itself when it is a path, without any query or
+/// fragment, or the path of an absolute URL.
+fn path_of(path_or_url: &str) -> Option<String> {
+    let input = path_or_url.trim();
+    if input.starts_with('/') {
+        return input.split(['?', '#']).next().map(str::to_string);
+    }
+    Url::parse(input).ok().map(|url| url.path().to_string())
+}
+
+fn params_to_js(params: Vec<(String, String)>) -> JsValue {
+    let obj = Object::new();
+    for (name, value) in params {
+        let _ = Reflect::set(&obj, &name.into(), &value.into());
+    }
+    obj.into()
+}
+
+fn read_params(params: &JsValue) -> Result<HashMap<String, String>, String> {
+    if params.is_null() || params.is_undefined() {
+        return Ok(HashMap::new());
+    }
+    if !params.is_object() || Array::is_array(params) {
+        return Err("params must be an object".to_string());
+    }
+
+    let mut values = HashMap::new();
+    for entry in Object::entries(&Object::from(params.clone())).iter() {
+        let name = Reflect::get_u32(&entry, 0)
+            .ok()
+            .and_then(|name| name.as_string())
+            .unwrap_or_default();
+        let value = Reflect::get_u32(&entry, 1).unwrap_or(JsValue::UNDEFINED);
+        let value = match value.as_string() {
+            Some(text) => text,
+            None => match value.as_f64() {
+                Some(number) => number.to_string(),
+                None if value.is_null() || value.is_undefined() => continue,
+                None => return Err(format!("param '{name}' must be a string or number")),
+            },
+        };
+        values.insert(name, value);
+    }
+    Ok(values)
+}
+
+/// A compiled route pattern such as `/users/:id(\d+)/posts/:slug?/*`, for
+/// matching many paths without reparsing.
+///
+/// Segments are literals, `:name` parameters, or a final `*` that captures the
+/// rest of the path (possibly empty) as `"*"`. A parameter followed by `?` is
+/// optional, and `(regex)` after its name constrains the decoded value; the
+/// regex must match the whole segment. Parameters never match an empty
+/// segment. A single trailing slash on the path is ignored, and captured
+/// values are percent-decoded.
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct UrlPattern {
+    route: Route,
+}
+
+#[wasm_bindgen]
+impl UrlPattern {
+    /// Whether `path` (a path or an absolute URL) matches.
+    pub fn test(&self, path: &str) -> bool {
+        self.route.exec(path).is_some()
+    }
+
+    /// The params of `path`, e.g. `{id: "42", "*": "2024/a-post"}`, or `null`
+    /// when it doesn't match. Absent optional params are left out.
+    pub fn exec(&self, path: &str) -> Option<JsValue> {
+        self.route.exec(path).map(params_to_js)
+    }
+}
+
+/// Compiles `pattern` into a reusable `UrlPattern`; see there for the syntax.
+#[wasm_bindgen]
+pub fn compile_url_pattern(pattern: &str) -> Result<UrlPattern, JsValue> {
+    Route::parse(pattern)
+        .map(|route| UrlPattern { route })
+        .map_err(|e| JsValue::from_str(&e))
+}
+
+/// Matches `path` against `pattern` once, returning the params object or
+/// `null`. Use `compile_url_pattern` in hot loops.
+#[wasm_bindgen]
+pub fn match_url_pattern(pattern: &str, path: &str) -> Result<Option<JsValue>, JsValue> {
+    Ok(compile_url_pattern(pattern)?.exec(path))
+}
+
+/// Fills `pattern` with `params` (strings or numbers), percent-encoding each
+/// value. Optional params may be left out; a missing required param or a
+/// value failing its constraint is an error naming the param. `"*"` may hold
+/// several segments separated by `/`.
+#[wasm_bindgen]
+pub fn build_path_from_pattern(pattern: &str, params: JsValue) -> Result<String, JsValue> {
+    let route = Route::parse(pattern).map_err(|e| JsValue::from_str(&e))?;
+    let params = read_params(&params).map_err(|e| JsValue::from_str(&e))?;
+    route.build(&params).map_err(|e| JsValue::from_str(&e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exec(pattern: &str, path: &str) -> Option<Vec<(String, String)>> {
+        Route::parse(pattern).unwrap().exec(path)
+    }
+
+    fn params(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        pairs
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    fn build(pattern: &str, pairs: &[(&str, &str)]) -> Result<String, String> {
+        let values = params(pairs).into_iter().collect();
+        Route::parse(pattern).unwrap().build(&values)
+    }
+
+    #[test]
+    fn extracts_params_and_wildcard() {
+        assert_eq!(
+            exec("/users/:id/posts/*", "/users/42/posts/2024/a-post"),
+            Some(params(&[("id", "42"), ("*", "2024/a-post")]))
+        );
+        assert_eq!(
+            exec("/users/:id/posts/*", "/users/42/posts"),
+            Some(params(&[("id", "42"), ("*", "")]))
+        );
+        assert_eq!(exec("/users/:id", "/users"), None);
+        assert_eq!(exec("/users/:id", "/users//"), None);
+        assert_eq!(exec("/users/:id", "/users/42/extra"), None);
+        assert_eq!(exec("/", "/"), Some(vec![]));
+    }
+
+    #[test]
+    fn optional_params() {
+        let pattern = "/posts/:year?/:slug";
+        assert_eq!(
+            exec(pattern, "/posts/2024/hello"),
+            Some(params(&[("year", "2024"), ("slug", "hello")]))
+        );
+        assert_eq!(
+            exec(pattern, "/posts/hello"),
+            Some(params(&[("slug", "hello")]))
+        );
+        assert_eq!(exec("/users/:id?", "/users"), Some(vec![]));
+        assert_eq!(exec("/users/:id?", "/users/"), Some(vec![]));
+    }
+
+    #[test]
+    fn constrained_params() {
+        let pattern = r"/users/:id(\d+)";
+        assert_eq!(exec(pattern, "/users/42"), Some(params(&[("id", "42")])));
+        assert_eq!(exec(pattern, "/users/me"), None);
+
+        let pattern = r"/docs/:version(v\d+)?/:page";
+        assert_eq!(
+            exec(pattern, "/docs/v2/intro"),
+            Some(params(&[("version", "v2"), ("page", "intro")]))
+        );
+        assert_eq!(
+            exec(pattern, "/docs/intro"),
+            Some(params(&[("page", "intro")]))
+        );
+
+        let pattern = r"/files/:name([^/]+\.json)";
+        assert!(exec(pattern, "/files/a%20b.json").is_some());
+        // Constraints see the decoded value, so an encoded slash counts.
+        assert!(exec(pattern, "/files/a%2Fb.json").is_none());
+        assert!(Route::parse(r"/x/:id(\d+").is_err());
+        assert!(Route::parse(r"/x/:id((?=a))").is_err());
+    }
+
+    #[test]
+    fn tolerates_trailing_slash_query_and_full_urls() {
+        for path in [
+            "/users/42",
+            "/users/42/",
+            "/users/42?tab=posts#top",
+            "https://example.com/users/42/?x=1",
+        ] {
+            assert_eq!(
+                exec("/users/:id", path),
+                Some(params(&[("id", "42")])),
+                "{path}"
+            );
+        }
+        assert_eq!(
+            exec("/users/:id/", "/users/42"),
+            Some(params(&[("id", "42")]))
+        );
+    }
+
+    #[test]
+    fn percent_decodes_params_and_literals() {
+        assert_eq!(
+            exec("/tags/:tag", "/tags/caf%C3%A9%20au%20lait"),
+            Some(params(&[("tag", "café au lait")]))
+        );
+        assert_eq!(
+            exec("/café/:x", "/caf%C3%A9/1"),
+            Some(params(&[("x", "1")]))
+        );
+        assert_eq!(
+            exec("/files/*", "/files/a%20b/c%2Fd"),
+            Some(params(&[("*", "a b/c/d")]))
+        );
+        assert_eq!(exec("/tags/:tag", "/tags/%FF"), None);
+    }
+
+    #[test]
+    fn rejects_bad_patterns() {
+        for pattern in [
+            "users/:id",
+            "/users/:",
+            "/users/:1d",
+            "/a/*/b",
+            "/:id/:id",
+            "/x?y",
+        ] {
+            assert!(Route::parse(pattern).is_err(), "{pattern}");
+        }
+    }
+
+    #[test]
+    fn builds_paths() {
+        assert_eq!(
+            build("/users/:id/posts/*", &[("id", "42"), ("*", "2024/a post")]).unwrap(),
+            "/users/42/posts/2024/a%20post"
+        );
+        assert_eq!(
+            build("/posts/:year?/:slug", &[("slug", "hi")]).unwrap(),
+            "/posts/hi"
+        );
+        assert_eq!(
+            build("/tags/:tag", &[("tag", "a/b?c")]).unwrap(),
+            "/tags/a%2Fb%3Fc"
+        );
+        assert_eq!(build("/", &[]).unwrap(), "/");
+        assert_eq!(build("/:page?", &[]).unwrap(), "/");
+
+        let error = build("/users/:id/posts/:slug", &[("id", "1")]).unwrap_err();
+        assert!(error.contains("'slug'"), "{error}");
+        let error = build(r"/users/:id(\d+)", &[("id", "me")]).unwrap_err();
+        assert!(error.contains("'id'"), "{error}");
+    }
+
+    #[test]
+    fn build_then_match_round_trips() {
+        let pattern = r"/u/:name/:n(\d+)?/*";
+        let values = [("name", "Zoë / x"), ("n", "7"), ("*", "a/b")];
+        let path = build(pattern, &values).unwrap();
+        assert_eq!(exec(pattern, &path), Some(params(&values)));
+    }
+}
@@ -0,0 +1,370 @@
+//! The regular expressions allowed as route parameter constraints, such as
+//! `\d+` or `[a-z0-9-]{3,}`, matched against a whole decoded segment.
+//!
+//! This is a small backtracking matcher for the subset of JS regex syntax
+//! routers use: literals, `.`, classes with ranges, `\d \w \s` and their
+//! negations, groups, alternation and quantifiers (lazy ones
+//! too, though laziness can't change whether a whole segment matches). Anything
+//! else, such as backreferences or lookaround, is rejected when the pattern
+//! is compiled rather than silently misread.
+
+/// Upper bound on `{n,m}` counts, which also keeps a hostile constraint from
+/// building a huge repetition.
+const MAX_REPEAT: u32 = 1000;
+
+#[derive(Clone, Debug, PartialEq)]
+enum Node {
+    Char(char),
+    Any,
+    Class {
+        ranges: Vec<(char, char)>,
+        negated: bool,
+    },
+    Group(Vec<Vec<Node>>),
+    Repeat {
+        node: Box<Node>,
+        min: u32,
+        max: Option<u32>,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub(super) struct Constraint {
+    source: String,
+    alternatives: Vec<Vec<Node>>,
+}
+
+const DIGIT: &[(char, char)] = &[('0', '9')];
+const WORD: &[(char, char)] = &[('0', '9'), ('A', 'Z'), ('_', '_'), ('a', 'z')];
+const SPACE: &[(char, char)] = &[
+    ('\t', '\r'),
+    (' ', ' '),
+    ('\u{a0}', '\u{a0}'),
+    ('\u{feff}', '\u{feff}'),
+];
+
+/// The ranges of `\d`, `\w` and `\s`, and whether the escape is negated.
+fn shorthand(c: char) -> Option<(&'static [(char, char)], bool)> {
+    Some(match c {
+        'd' => (DIGIT, false),
+        'D' => (DIGIT, true),
+        'w' => (WORD, false),
+        'W' => (WORD, true),
+        's' => (SPACE, false),
+        'S' => (SPACE, true),
+        _ => return None,
+    })
+}
+
+/// An escaped literal: punctuation, or one of the control escapes.
+fn escaped_literal(c: char) -> Option<char> {
+    match c {
+        'n' => Some('\n'),
+        't' => Some('\t'),
+        'r' => Some('\r'),
+        c if c.is_ascii_punctuation() => Some(c),
+        _ => None,
+    }
+}
+
+struct Parser<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+}
+
+impl Parser<'_> {
+    fn alternatives(&mut self, depth: u32) -> Result<Vec<Vec<Node>>, String> {
+        let mut alternatives = vec![self.sequence(depth)?];
+        while self.chars.next_if_eq(&'|').is_some() {
+            alternatives.push(self.sequence(depth)?);
+        }
+        Ok(alternatives)
+    }
+
+    fn sequence(&mut self, depth: u32) -> Result<Vec<Node>, String> {
+        let mut nodes = Vec::new();
+        while let Some(&c) = self.chars.peek() {
+            let atom = match c {
+                '|' => break,
+                ')' if depth > 0 => break,
+                _ => self.atom(depth)?,
+            };
+            nodes.push(self.quantified(atom)?);
+        }
+        Ok(nodes)
+    }
+
+    fn atom(&mut self, depth: u32) -> Result<Node, String> {
+        match self.chars.next() {
+            Some('.') => Ok(Node::Any),
+            Some('[') => self.class(),
+            Some('(') => {
+                if self.chars.next_if_eq(&'?').is_some() && self.chars.next() != Some(':') {
+                    return Err("only non-capturing '(?:…)' groups are supported".to_string());
+                }
+                let alternatives = self.alternatives(depth + 1)?;
+                if self.chars.next() != Some(')') {
+                    return Err("unclosed group".to_string());
+                }
+                Ok(Node::Group(alternatives))
+            }
+            Some('\\') => {
+                let c = self.chars.next().ok_or("trailing backslash")?;
+                if let Some((ranges, negated)) = shorthand(c) {
+                    return Ok(Node::Class {
+                        ranges: ranges.to_vec(),
+                        negated,
+                    });
+                }
+                escaped_literal(c)
+                    .map(Node::Char)
+                    .ok_or_else(|| format!("unsupported escape '\\{c}'"))
+            }
+            Some(c @ ('*' | '+' | '?' | '{')) => Err(format!("nothing to repeat before '{c}'")),
+            Some(c @ (')' | ']' | '}' | '^' | '$')) => Err(format!("unexpected '{c}'")),
+            Some(c) => Ok(Node::Char(c)),
+            None => Err("unexpected end of pattern".to_string()),
+        }
+    }
+
+    fn class(&mut self) -> Result<Node, String> {
+        let negated = self.chars.next_if_eq(&'^').is_some();
+        let mut ranges = Vec::new();
+        let mut first = true;
+
+        loop {
+            let c = match self.chars.next() {
+                None => return Err("unclosed character class".to_string()),
+                Some(']') if !first => break,
+                Some(c) => c,
+            };
+            first = false;
+
+            let start = match c {
+                '\\' => {
+                    let c = self.chars.next().ok_or("trailing backslash")?;
+                    if let Some((shorthand, false)) = shorthand(c) {
+                        ranges.extend_from_slice(shorthand);
+                        continue;
+                    }
+                    escaped_literal(c)
+                        .ok_or_else(|| format!("unsupported escape '\\{c}' in class"))?
+                }
+                c => c,
+            };
+
+            let mut lookahead = self.chars.clone();
+            if lookahead.next() == Some('-') && lookahead.peek().is_some_and(|&c| c != ']') {
+                self.chars.next();
+                let end = match self.chars.next() {
+                    Some('\\') => {
+                        let c = self.chars.next().ok_or("trailing backslash")?;
+                        escaped_literal(c).ok_or_else(|| format!("invalid range end '\\{c}'"))?
+                    }
+                    Some(c) => c,
+                    None => return Err("unclosed character class".to_string()),
+                };
+                if end < start {
+                    return Err(format!("range out of order '{start}-{end}'"));
+                }
+                ranges.push((start, end));
+            } else {
+                ranges.push((start, start));
+            }
+        }
+
+        Ok(Node::Class { ranges, negated })
+    }
+
+    fn quantified(&mut self, atom: Node) -> Result<Node, String> {
+        let (min, max) = match self.chars.peek() {
+            Some('*') => (0, None),
+            Some('+') => (1, None),
+            Some('?') => (0, Some(1)),
+            Some('{') => {
+                self.chars.next();
+                let bounds = self.bounds()?;
+                return self.repeat(atom, bounds);
+            }
+            _ => return Ok(atom),
+        };
+        self.chars.next();
+        self.repeat(atom, (min, max))
+    }
+
+    fn bounds(&mut self) -> Result<(u32, Option<u32>), String> {
+        let mut body = String::new();
+        loop {
+            match self.chars.next() {
+                Some('}') => break,
+                Some(c) => body.push(c),
+                None => return Err("unclosed '{'".to_string()),
+            }
+        }
+        let number = |s: &str| {
+            s.parse::<u32>()
+                .ok()
+                .filter(|&n| n <= MAX_REPEAT)
+                .ok_or_else(|| format!("invalid repeat count '{{{body}}}'"))
+        };
+        let (min, max) = match body.split_once(',') {
+            None => {
+                let n = number(&body)?;
+                (n, Some(n))
+            }
+            Some((min, "")) => (number(min)?, None),
+            Some((min, max)) => (number(min)?, Some(number(max)?)),
+        };
+        if max.is_some_and(|max| max < min) {
+            return Err(format!("repeat count out of order '{{{body}}}'"));
+        }
+        Ok((min, max))
+    }
+
+    fn repeat(&mut self, atom: Node, (min, max): (u32, Option<u32>)) -> Result<Node, String> {
+        // A lazy `?` suffix only changes which match is found first.
+        self.chars.next_if_eq(&'?');
+        Ok(Node::Repeat {
+            node: Box::new(atom),
+            min,
+            max,
+        })
+    }
+}
+
+impl Constraint {
+    pub(super) fn parse(source: &str) -> Result<Self, String> {
+        // Constraints always match the whole segment, so explicit anchors are
+        // redundant but harmless.
+        let body = source.strip_prefix('^').unwrap_or(source);
+        let body = match body.strip_suffix('$') {
+            Some(stripped) if !stripped.ends_with('\\') => stripped,
+            _ => body,
+        };
+
+        let mut parser = Parser {
+            chars: body.chars().peekable(),
+        };
+        let alternatives = parser
+            .alternatives(0)
+            .map_err(|e| format!("constraint '({source})': {e}"))?;
+        if parser.chars.next().is_some() {
+            return Err(format!("constraint '({source})': unbalanced ')'"));
+        }
+        Ok(Self {
+            source: source.to_string(),
+            alternatives,
+        })
+    }
+
+    pub(super) fn source(&self) -> &str {
+        &self.source
+    }
+
+    pub(super) fn is_match(&self, text: &str) -> bool {
+        let input: Vec<char> = text.chars().collect();
+        self.alternatives
+            .iter()
+            .any(|sequence| sequence_matches(sequence, &input, 0, &|end| end == input.len()))
+    }
+}
+
+/// Whether `nodes` match `input` from `pos` such that `rest` accepts the end
+/// position, backtracking through the choices.
+fn sequence_matches(
+    nodes: &[Node],
+    input: &[char],
+    pos: usize,
+    rest: &dyn Fn(usize) -> bool,
+) -> bool {
+    let Some((node, tail)) = nodes.split_first() else {
+        return rest(pos);
+    };
+    let next = |end: usize| sequence_matches(tail, input, end, rest);
+    match node {
+        Node::Repeat { node, min, max } => repeat_matches(node, (*min, *max), 0, input, pos, &next),
+        _ => node_matches(node, input, pos, &next),
+    }
+}
+
+fn node_matches(node: &Node, input: &[char], pos: usize, rest: &dyn Fn(usize) -> bool) -> bool {
+    match node {
+        Node::Char(c) => input.get(pos) == Some(c) && rest(pos + 1),
+        Node::Any => input.get(pos).is_some_and(|&c| c != '\n') && rest(pos + 1),
+        Node::Class { ranges, negated } => {
+            input.get(pos).is_some_and(|&c| {
+                ranges
+                    .iter()
+                    .any(|&(start, end)| (start..=end).contains(&c))
+                    != *negated
+            }) && rest(pos + 1)
+        }
+        Node::Group(alternatives) => alternatives
+            .iter()
+            .any(|sequence| sequence_matches(sequence, input, pos, rest)),
+        Node::Repeat { .. } => sequence_matches(std::slice::from_ref(node), input, pos, rest),
+    }
+}
+
+fn repeat_matches(
+    node: &Node,
+    (min, max): (u32, Option<u32>),
+    count: u32,
+    input: &[char],
+    pos: usize,
+    rest: &dyn Fn(usize) -> bool,
+) -> bool {
+    let more = max.is_none_or(|max| count < max)
+        && node_matches(node, input, pos, &|end| {
+            // Once the minimum is met, an empty iteration can't make progress.
+            (end > pos || count < min)
+                && repeat_matches(node, (min, max), count + 1, input, end, rest)
+        });
+    more || (count >= min && rest(pos))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn is_match(pattern: &str, text: &str) -> bool {
+        Constraint::parse(pattern).unwrap().is_match(text)
+    }
+
+    #[test]
+    fn matches_whole_segments() {
+        assert!(is_match(r"\d+", "42"));
+        assert!(!is_match(r"\d+", "42a"));
+        assert!(!is_match(r"\d+", ""));
+        assert!(is_match(r"^\d{4}$", "2024"));
+        assert!(!is_match(r"\d{4}", "202"));
+        assert!(is_match(r"[a-z0-9-]{3,}", "my-post-1"));
+        assert!(!is_match(r"[a-z0-9-]{3,}", "My-post"));
+        assert!(is_match(r"[^/]+\.json", "data.json"));
+        assert!(!is_match(r"[^/]+\.json", "dataxjson"));
+        assert!(is_match(r"new|edit", "edit"));
+        assert!(!is_match(r"new|edit", "newedit"));
+        assert!(is_match(r"(?:ab)+c?", "ababc"));
+        assert!(is_match(r"v\d+(\.\d+)*", "v1.2.3"));
+        assert!(is_match(r"\w+\s?\W", "a_1 !"));
+        assert!(is_match(r"a.*?z", "abcz"));
+        assert!(is_match(r"[\d_]{2}", "4_"));
+        assert!(is_match(r"[-a]", "-"));
+    }
+
+    #[test]
+    fn terminates_on_empty_repeats() {
+        assert!(is_match(r"(a*)*b", "aaab"));
+        assert!(!is_match(r"(a*)*b", "aaac"));
+        assert!(is_match(r"(?:)*x", "x"));
+    }
+
+    #[test]
+    fn rejects_unsupported_syntax() {
+        for pattern in [
+            r"(?=a)", r"\1", r"a**", r"[a", r"(a", r"a)", r"*a", r"a{2,1}", r"a{5000}", r"[z-a]",
+            r"\p{L}",
+        ] {
+            assert!(Constraint::parse(pattern).is_err(), "{pattern}");
+        }
+    }
+}
@@ -0,0 +1,365 @@
+use std::fmt;
+
+use js_sys::Reflect;
+use wasm_bindgen::prelude::*;
+
+/// Format written by `encode`: the state stored as is. The payload is
+/// `[version][length as LEB128][state bytes][Adler-32 of the state, BE]`,
+/// base64url-encoded without padding.
+const VERSION_STORED: u8 = 1;
+/// Reserved for the same layout with a deflate-compressed body, once a
+/// deflate implementation is available to the crate. Decoding it fails with
+/// `unsupported_version` until then.
+#[allow(dead_code)]
+const VERSION_DEFLATE: u8 = 2;
+
+/// Largest state accepted in either direction, so a hostile link can't make
+/// the page allocate without bound.
+const MAX_STATE_BYTES: usize = 1 << 20;
+
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+#[derive(Debug, PartialEq)]
+enum StateError {
+    Empty,
+    Corrupt(&'static str),
+    Truncated,
+    UnsupportedVersion(u8),
+    TooLarge(usize),
+    InvalidUtf8,
+}
+
+impl StateError {
+    fn code(&self) -> &'static str {
+        match self {
+            Self::Empty => "empty",
+            Self::Corrupt(_) => "corrupt",
+            Self::Truncated => "truncated",
+            Self::UnsupportedVersion(_) => "unsupported_version",
+            Self::TooLarge(_) => "too_large",
+            Self::InvalidUtf8 => "invalid_utf8",
+        }
+    }
+}
+
+impl fmt::Display for StateError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "state fragment is empty"),
+            Self::Corrupt(why) => write!(f, "state fragment is corrupt: {why}"),
+            Self::Truncated => write!(f, "state fragment is truncated"),
+            Self::UnsupportedVersion(v) => write!(f, "unsupported state format version {v}"),
+            Self::TooLarge(len) => write!(
+                f,
+                "state of {len} bytes exceeds the limit of {MAX_STATE_BYTES}"
+            ),
+            Self::InvalidUtf8 => write!(f, "state is not valid UTF-8"),
+        }
+    }
+}
+
+/// A JS `Error` with the message and a `code` property to branch on.
+fn to_js(error: StateError) -> JsValue {
+    let js = js_sys::Error::new(&error.to_string());
+    let _ = Reflect::set(&js, &"code".into(), &error.code().into());
+    js.into()
+}
+
+fn adler32(bytes: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for chunk in bytes.chunks(5552) {
+        for &byte in chunk {
+            a += u32::from(byte);
+            b += a;
+        }
+        a %= 65521;
+        b %= 65521;
+    }
+    (b << 16) | a
+}
+
+fn varint_len(mut n: usize) -> usize {
+    let mut len = 1;
+    while n >= 0x80 {
+        n >>= 7;
+        len += 1;
+    }
+    len
+}
+
+fn push_varint(out: &mut Vec<u8>, mut n: usize) {
+    while n >= 0x80 {
+        out.push((n as u8 & 0x7f) | 0x80);
+        n >>= 7;
+    }
+    out.push(n as u8);
+}
+
+/// Reads a LEB128 length, returning it and the bytes after it.
+fn read_varint(bytes: &[u8]) -> Result<(usize, &[u8]), StateError> {
+    let mut n = 0usize;
+    for (i, &byte) in bytes.iter().enumerate() {
+        if i >= varint_len(MAX_STATE_BYTES) {
+            return Err(StateError::Corrupt("length is too long"));
+        }
+        n |= usize::from(byte & 0x7f) << (7 * i);
+        if byte & 0x80 == 0 {
+            return Ok((n, &bytes[i + 1..]));
+        }
+    }
+    Err(StateError::Truncated)
+}
+
+fn base64_len(bytes: usize) -> usize {
+    (bytes * 4).div_ceil(3)
+}
+
+fn base64url(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(base64_len(bytes.len()));
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, &b)| n | u32::from(b) << (16 - 8 * i));
+        for i in 0..=chunk.len() {
+            out.push(ALPHABET[(n >> (18 - 6 * i)) as usize & 63] as char);
+        }
+    }
+    out
+}
+
+fn unbase64url(text: &str) -> Result<Vec<u8>, StateError> {
+    if text.len() % 4 == 1 {
+        return Err(StateError::Truncated);
+    }
+    let mut out = Vec::with_capacity(text.len() * 3 / 4);
+    for chunk in text.as_bytes().chunks(4) {
+        let mut n = 0u32;
+        for (i, &c) in chunk.iter().enumerate() {
+            let value = ALPHABET
+                .iter()
+                .position(|&a| a == c)
+                .ok_or(StateError::Corrupt("not base64url"))?;
+            n |= (value as u32) << (18 - 6 * i);
+        }
+        out.extend_from_slice(&n.to_be_bytes()[1..chunk.len()]);
+    }
+    Ok(out)
+}
+
+fn payload_len(state_len: usize) -> usize {
+    1 + varint_len(state_len) + state_len + 4
+}
+
+fn encode(state: &str) -> Result<String, StateError> {
+    let bytes = state.as_bytes();
+    if bytes.len() > MAX_STATE_BYTES {
+        return Err(StateError::TooLarge(bytes.len()));
+    }
+
+    let mut payload = Vec::with_capacity(payload_len(bytes.len()));
+    payload.push(VERSION_STORED);
+    push_varint(&mut payload, bytes.len());
+    payload.extend_from_slice(bytes);
+    payload.extend_from_slice(&adler32(bytes).to_be_bytes());
+    Ok(base64url(&payload))
+}
+
+fn decode(fragment: &str) -> Result<String, StateError> {
+    let fragment = fragment.trim();
+    let fragment = fragment.strip_prefix('#').unwrap_or(fragment);
+    if fragment.is_empty() {
+        return Err(StateError::Empty);
+    }
+    // Checked before decoding anything, so oversized input costs nothing.
+    if fragment.len() > base64_len(payload_len(MAX_STATE_BYTES)) {
+        return Err(StateError::TooLarge(fragment.len() * 3 / 4));
+    }
+
+    let payload = unbase64url(fragment)?;
+    let (&version, rest) = payload.split_first().ok_or(StateError::Truncated)?;
+    if version != VERSION_STORED {
+        return Err(StateError::UnsupportedVersion(version));
+    }
+    let (len, rest) = read_varint(rest)?;
+    if len > MAX_STATE_BYTES {
+        return Err(StateError::TooLarge(len));
+    }
+    if rest.len() < len + 4 {
+        return Err(StateError::Truncated);
+    }
+    if rest.len() > len + 4 {
+        return Err(StateError::Corrupt("trailing data"));
+    }
+
+    let (bytes, checksum) = rest.split_at(len);
+    if adler32(bytes).to_be_bytes() != checksum {
+        return Err(StateError::Corrupt("checksum mismatch"));
+    }
+    String::from_utf8(bytes.to_vec()).map_err(|_| StateError::InvalidUtf8)
+}
+
+/// Encodes `json` for the URL hash: a version byte, the length, the state and
+/// a checksum, base64url-encoded without padding. The state is stored
+/// uncompressed for now; the version byte lets a compressed format follow
+/// without breaking existing links. Fails with `code: "too_large"` above
+/// 1 MiB.
+#[wasm_bindgen]
+pub fn encode_state_to_fragment(json: &str) -> Result<String, JsValue> {
+    encode(json).map_err(to_js)
+}
+
+/// Reverses `encode_state_to_fragment`; a leading `#` is ignored. Errors carry
+/// a `code` of `empty`, `corrupt`, `truncated`, `unsupported_version`,
+/// `too_large` or `invalid_utf8`.
+#[wasm_bindgen]
+pub fn decode_state_from_fragment(fragment: &str) -> Result<String, JsValue> {
+    decode(fragment).map_err(to_js)
+}
+
+/// Length of the fragment `encode_state_to_fragment` would produce for
+/// `json`, without the `#`, so the UI can warn before links get too long
+/// (around 2000 characters).
+#[wasm_bindgen]
+pub fn state_fragment_size(json: &str) -> usize {
+    base64_len(payload_len(json.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// xorshift64*, enough to vary the generated documents deterministically.
+    struct Rng(u64);
+
+    impl Rng {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 >> 12;
+            self.0 ^= self.0 << 25;
+            self.0 ^= self.0 >> 27;
+            self.0.wrapping_mul(0x2545_F491_4F6C_DD1D)
+        }
+
+        fn below(&mut self, n: u64) -> u64 {
+            self.next() % n
+        }
+    }
+
+    fn json_string(rng: &mut Rng) -> String {
+        const CHARS: &[char] = &['a', 'Z', '0', ' ', 'é', '😀', '\\', '"', '#', '%', '/'];
+        let text: String = (0..rng.below(12))
+            .map(|_| CHARS[rng.below(CHARS.len() as u64) as usize])
+            .collect();
+        format!("{:?}", text)
+    }
+
+    fn json_value(rng: &mut Rng, depth: u32) -> String {
+        match rng.below(if depth > 3 { 4 } else { 6 }) {
+            0 => "null".to_string(),
+            1 => (rng.below(2) == 1).to_string(),
+            2 => (rng.next() as i64 >> rng.below(60)).to_string(),
+            3 => json_string(rng),
+            4 => {
+                let items: Vec<String> = (0..rng.below(5))
+                    .map(|_| json_value(rng, depth + 1))
+                    .collect();
+                format!("[{}]", items.join(","))
+            }
+            _ => {
+                let fields: Vec<String> = (0..rng.below(5))
+                    .map(|_| format!("{}:{}", json_string(rng), json_value(rng, depth + 1)))
+                    .collect();
+                format!("{{{}}}", fields.join(","))
+            }
+        }
+    }
+
+    #[test]
+    fn round_trips_random_json() {
+        let mut rng = Rng(0x9E37_79B9_7F4A_7C15);
+        for _ in 0..500 {
+            let json = json_value(&mut rng, 0);
+            let fragment = encode(&json).unwrap();
+            assert!(
+                fragment
+                    .bytes()
+                    .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_'),
+                "{fragment}"
+            );
+            assert_eq!(fragment.len(), state_fragment_size(&json));
+            assert_eq!(decode(&fragment).unwrap(), json);
+            assert_eq!(decode(&format!("#{fragment}")).unwrap(), json);
+        }
+    }
+
+    #[test]
+    fn base64url_matches_known_vectors() {
+        for (bytes, text) in [
+            (&b""[..], ""),
+            (b"f", "Zg"),
+            (b"fo", "Zm8"),
+            (b"foo", "Zm9v"),
+            (b"\xfb\xff", "-_8"),
+        ] {
+            assert_eq!(base64url(bytes), text);
+            assert_eq!(unbase64url(text).unwrap(), bytes);
+        }
+    }
+
+    #[test]
+    fn errors_are_distinguishable() {
+        let fragment = encode(r#"{"zoom":3,"layers":["a","b"]}"#).unwrap();
+
+        assert_eq!(decode("").unwrap_err(), StateError::Empty);
+        assert_eq!(decode("#").unwrap_err(), StateError::Empty);
+        assert_eq!(
+            decode(&fragment[..fragment.len() - 4]).unwrap_err(),
+            StateError::Truncated
+        );
+        assert_eq!(
+            decode(&fragment[..fragment.len() - 3]).unwrap_err(),
+            StateError::Truncated
+        );
+        assert_eq!(
+            decode(&format!("{fragment}AAAA")).unwrap_err().code(),
+            "corrupt"
+        );
+        assert_eq!(decode("ab$d").unwrap_err().code(), "corrupt");
+
+        let mut flipped = fragment.into_bytes();
+        flipped[8] = if flipped[8] == b'A' { b'B' } else { b'A' };
+        let flipped = String::from_utf8(flipped).unwrap();
+        assert_eq!(decode(&flipped).unwrap_err().code(), "corrupt");
+
+        let future = base64url(&[VERSION_DEFLATE, 0, 0, 0, 0, 1]);
+        assert_eq!(
+            decode(&future).unwrap_err(),
+            StateError::UnsupportedVersion(VERSION_DEFLATE)
+        );
+
+        let bad_utf8 = [
+            &[VERSION_STORED, 1, 0xff][..],
+            &adler32(&[0xff]).to_be_bytes(),
+        ]
+        .concat();
+        assert_eq!(
+            decode(&base64url(&bad_utf8)).unwrap_err(),
+            StateError::InvalidUtf8
+        );
+    }
+
+    #[test]
+    fn caps_state_size() {
+        let huge = "x".repeat(MAX_STATE_BYTES + 1);
+        assert_eq!(encode(&huge).unwrap_err().code(), "too_large");
+        assert_eq!(
+            decode(&"A".repeat(2_000_000)).unwrap_err().code(),
+            "too_large"
+        );
+
+        // A small fragment claiming a huge length is rejected before allocating.
+        let mut lying = vec![VERSION_STORED];
+        push_varint(&mut lying, MAX_STATE_BYTES + 1);
+        assert_eq!(decode(&base64url(&lying)).unwrap_err().code(), "too_large");
+    }
+}